@@ -1,52 +1,123 @@
+use crate::application::events::forward_client_events;
+use crate::application::server_registry::{ServerSession, ServerRegistry};
 use crate::application::state::AppState;
-use crate::domain::mcp_types::Tool;
-use crate::infrastructure::proper_mcp_client::ClientState;
-use crate::infrastructure::mcp_transport::ServerConfig;
+use crate::domain::mcp_types::{
+    ElicitAction, GetPromptResponse, ListPromptsResponse, ListResourceTemplatesResponse, ListResourcesResponse,
+    ListToolsResponse, LoggingLevel, Prompt, ReadResourceResponse, Resource, ResourceTemplate, Root, Tool,
+    ToolContent,
+};
+use crate::domain::json_schema::SchemaViolation;
+use crate::domain::uri_template;
+use crate::infrastructure::elicitation::ElicitationError;
+use crate::infrastructure::proper_mcp_client::{ClientState, McpClientError, ToolCallOptions};
+use crate::infrastructure::mcp_transport::{RestartPolicy, ServerConfig, ShutdownConfig, TimeoutConfig, TransportKind};
+use crate::infrastructure::roots::RootsHandler;
+use crate::infrastructure::sampling::SamplingProviderKind;
+use crate::infrastructure::server_log::{LogEntry, LogFilter};
+use crate::infrastructure::stderr_log::StderrLine;
+use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use tauri::State;
-use tracing::{error, info};
+use tauri::{AppHandle, State};
+use tracing::{error, info, warn};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StartServerRequest {
+    pub server_id: String,
     pub command: String,
     pub args: Vec<String>,
     pub cwd: Option<String>,
     pub env: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub transport: TransportKind,
+    #[serde(default)]
+    pub timeouts: TimeoutConfig,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub shutdown: ShutdownConfig,
+    /// Answers the server's sampling requests once the user approves them.
+    /// Without one the server isn't offered sampling.
+    #[serde(default)]
+    pub sampling_provider: Option<SamplingProviderKind>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConnectionStatusResponse {
     pub status: String,
     pub message: Option<String>,
+    pub protocol_version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServerSummary {
+    pub server_id: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub status: String,
+    pub message: Option<String>,
+    pub tool_count: usize,
+    pub protocol_version: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CallToolRequest {
+    pub server_id: String,
     pub tool_name: String,
     pub arguments: Option<Value>,
+    /// Chosen by the frontend so the call can be cancelled with
+    /// `cancel_tool_call`. Also used as the progress token, so
+    /// `mcp://tool-progress` events for the call carry this id.
+    #[serde(default)]
+    pub call_id: Option<String>,
+    /// Keep waiting as long as the server keeps reporting progress.
+    #[serde(default)]
+    pub reset_timeout_on_progress: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Result of a tool call that reached the tool. Failures inside the tool
+/// come back here with `is_error` set; transport and protocol failures are
+/// returned as command errors instead.
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct CallToolResponse {
     pub content: Vec<ToolContent>,
-    pub is_error: Option<bool>,
+    pub is_error: bool,
+    pub structured_content: Option<Value>,
+    /// Why the call was cancelled, if it was; `content` is then empty.
+    pub cancelled: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ToolContent {
-    #[serde(rename = "type")]
-    pub content_type: String,
-    pub text: Option<String>,
+fn status_parts(client_state: ClientState) -> (String, Option<String>) {
+    let (status_str, message) = match client_state {
+        ClientState::Disconnected => ("disconnected", None),
+        ClientState::Connecting => ("connecting", None),
+        ClientState::Connected => ("connected", None),
+        ClientState::Error(msg) => ("error", Some(msg)),
+    };
+    (status_str.to_string(), message)
+}
+
+async fn get_session(servers: &ServerRegistry, server_id: &str) -> Result<Arc<ServerSession>, String> {
+    servers.get(server_id).await
+        .ok_or_else(|| format!("Unknown server: {}", server_id))
+}
+
+/// Closes a session that a newly started one replaced in the registry.
+async fn close_replaced(previous: &ServerSession) {
+    info!("Replacing existing session for server '{}'", previous.id);
+    if let Err(e) = previous.close().await {
+        error!("Error disconnecting previous session '{}': {}", previous.id, e);
+    }
 }
 
 #[tauri::command]
 pub async fn start_mcp_server(
     request: StartServerRequest,
+    app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
-    info!("Starting MCP server: {} {:?}", request.command, request.args);
+    info!("Starting MCP server '{}': {} {:?}", request.server_id, request.command, request.args);
     
     // Create server config
     let config = ServerConfig {
@@ -54,28 +125,70 @@ pub async fn start_mcp_server(
         args: request.args.clone(),
         cwd: request.cwd.clone(),
         env: request.env.clone(),
+        transport: request.transport.clone(),
+        timeouts: request.timeouts.clone(),
+        restart: request.restart.clone(),
+        shutdown: request.shutdown.clone(),
     };
 
-    let mut client = state.mcp_client.lock().await;
+    let sampling_provider = request.sampling_provider.and_then(|kind| state.sampling_provider(kind));
+    let session = Arc::new(ServerSession::new(request.server_id.clone(), config, sampling_provider));
+    {
+        let client = session.client.lock().await;
+        forward_client_events(app, session.id.clone(), client.subscribe_events());
+        client.request_handlers().register(
+            "roots/list",
+            Arc::new(RootsHandler::new(state.roots.clone(), session.id.clone())),
+        );
+    }
+
+    // Registered before connecting so the connection can be watched and
+    // aborted. Starting under an id that is already in use replaces that session.
+    if let Some(previous) = state.servers.insert(session.clone()).await {
+        close_replaced(&previous).await;
+    }
+    let result = session.connect().await;
+    if result.is_err() {
+        state.servers.remove_session(&session).await;
+    }
     
-    match client.connect(config).await {
+    match result {
         Ok(()) => {
-            info!("MCP server started successfully");
+            info!("MCP server '{}' started successfully", request.server_id);
             Ok("Server started successfully".to_string())
         }
         Err(e) => {
-            error!("Failed to connect to MCP server: {}", e);
+            error!("Failed to connect to MCP server '{}': {}", request.server_id, e);
             Err(format!("Failed to connect: {}", e))
         }
     }
 }
 
 #[tauri::command]
-pub async fn discover_tools(state: State<'_, AppState>) -> Result<Vec<Tool>, String> {
-    info!("Discovering tools");
+pub async fn list_servers(state: State<'_, AppState>) -> Result<Vec<ServerSummary>, String> {
+    let mut summaries = Vec::new();
+    for session in state.servers.list().await {
+        let client = &session.handle;
+        let (status, message) = status_parts(client.get_state().await);
+        summaries.push(ServerSummary {
+            server_id: session.id.clone(),
+            command: session.config.command.clone(),
+            args: session.config.args.clone(),
+            status,
+            message,
+            tool_count: client.get_tools().await.len(),
+            protocol_version: client.protocol_version().map(|version| version.to_string()),
+        });
+    }
+    Ok(summaries)
+}
+
+#[tauri::command]
+pub async fn discover_tools(server_id: String, state: State<'_, AppState>) -> Result<Vec<Tool>, String> {
+    info!("Discovering tools for server '{}'", server_id);
     
-    let client = state.mcp_client.lock().await;
-    match client.list_tools().await {
+    let session = get_session(&state.servers, &server_id).await?;
+    match session.handle.list_tools().await {
         Ok(tools) => Ok(tools),
         Err(e) => {
             error!("Failed to discover tools: {}", e);
@@ -84,47 +197,116 @@ pub async fn discover_tools(state: State<'_, AppState>) -> Result<Vec<Tool>, Str
     }
 }
 
+/// Returns the tools page at `cursor`, or the first page without one. Unlike
+/// `discover_tools`, this leaves the cached tool list alone.
 #[tauri::command]
-pub async fn get_connection_status(state: State<'_, AppState>) -> Result<ConnectionStatusResponse, String> {
-    let client = state.mcp_client.lock().await;
-    let client_state = client.get_state().await;
-    
-    let (status_str, message) = match client_state {
-        ClientState::Disconnected => ("disconnected", None),
-        ClientState::Connecting => ("connecting", None),
-        ClientState::Connected => ("connected", None),
-        ClientState::Error(msg) => ("error", Some(msg)),
-    };
+pub async fn list_tools_page(
+    server_id: String,
+    cursor: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ListToolsResponse, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.list_tools_page(cursor).await.map_err(|e| {
+        error!("Failed to list tools: {}", e);
+        format!("Failed to list tools: {}", e)
+    })
+}
+
+#[tauri::command]
+pub async fn get_connection_status(server_id: String, state: State<'_, AppState>) -> Result<ConnectionStatusResponse, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    let client = &session.handle;
+    let (status, message) = status_parts(client.get_state().await);
     
     Ok(ConnectionStatusResponse {
-        status: status_str.to_string(),
+        status,
         message,
+        protocol_version: client.protocol_version().map(|version| version.to_string()),
     })
 }
 
+/// Returns what the server has written to stderr, oldest first, limited to
+/// the last `limit` lines. New lines arrive as `mcp://server-stderr` events.
+#[tauri::command]
+pub async fn get_server_stderr(
+    server_id: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<StderrLine>, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    Ok(session.handle.stderr(limit))
+}
+
+/// Sets the minimum level of log messages the server sends.
+#[tauri::command]
+pub async fn set_server_log_level(
+    server_id: String,
+    level: LoggingLevel,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.set_log_level(level).await.map_err(|e| {
+        error!("Failed to set log level: {}", e);
+        format!("Failed to set log level: {}", e)
+    })
+}
+
+/// Returns the server's log messages matching `filter`, oldest first. New
+/// messages arrive as `mcp://notification` events of type `logging_message`.
+#[tauri::command]
+pub async fn get_server_logs(
+    server_id: String,
+    filter: Option<LogFilter>,
+    state: State<'_, AppState>,
+) -> Result<Vec<LogEntry>, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    Ok(session.handle.logs(&filter.unwrap_or_default()))
+}
+
+/// Lists the loggers the server has sent messages from, for filtering.
+#[tauri::command]
+pub async fn list_server_loggers(server_id: String, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    Ok(session.handle.loggers())
+}
+
 #[tauri::command]
 pub async fn call_tool(
     request: CallToolRequest,
     state: State<'_, AppState>,
 ) -> Result<CallToolResponse, String> {
-    info!("Calling tool: {} with args: {:?}", request.tool_name, request.arguments);
+    info!("Calling tool: {} on server '{}' with args: {:?}", request.tool_name, request.server_id, request.arguments);
     
-    let client = state.mcp_client.lock().await;
-    match client.call_tool(&request.tool_name, request.arguments).await {
+    let session = get_session(&state.servers, &request.server_id).await?;
+    let options = ToolCallOptions {
+        cancellation: request.call_id.as_deref().map(|call_id| session.begin_tool_call(call_id)),
+        progress_token: request.call_id.clone(),
+        reset_timeout_on_progress: request.reset_timeout_on_progress,
+    };
+    let result = session.handle.call_tool_with(&request.tool_name, request.arguments, options).await;
+    if let Some(ref call_id) = request.call_id {
+        session.end_tool_call(call_id);
+    }
+    
+    match result {
         Ok(response) => {
-            info!("Tool call successful: {:?}", response);
+            if response.is_error {
+                warn!("Tool {} reported an error: {:?}", request.tool_name, response.content);
+            } else {
+                info!("Tool call successful: {:?}", response);
+            }
+            Ok(CallToolResponse {
+                content: response.content,
+                is_error: response.is_error,
+                structured_content: response.structured_content,
+                cancelled: None,
+            })
+        }
+        Err(McpClientError::Cancelled(reason)) => {
+            info!("Tool call {} cancelled: {}", request.tool_name, reason);
             Ok(CallToolResponse {
-                content: response.content.into_iter().map(|c| match c {
-                    crate::domain::mcp_types::ToolContent::Text { text } => ToolContent {
-                        content_type: "text".to_string(),
-                        text: Some(text),
-                    },
-                    crate::domain::mcp_types::ToolContent::Image { data, mime_type: _ } => ToolContent {
-                        content_type: "image".to_string(),
-                        text: Some(data),
-                    },
-                }).collect(),
-                is_error: None, // MCP protocol doesn't have is_error field
+                cancelled: Some(reason),
+                ..Default::default()
             })
         }
         Err(e) => {
@@ -134,16 +316,297 @@ pub async fn call_tool(
     }
 }
 
+/// Cancels an in-flight `call_tool` started with the given `call_id`. The
+/// pending call then returns with `cancelled` set.
+#[tauri::command]
+pub async fn cancel_tool_call(
+    server_id: String,
+    call_id: String,
+    reason: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    let reason = reason.unwrap_or_else(|| "Cancelled by user".to_string());
+    if session.cancel_tool_call(&call_id, &reason) {
+        info!("Cancelled tool call {} on server '{}'", call_id, server_id);
+        Ok(())
+    } else {
+        Err(format!("No tool call in progress with id: {}", call_id))
+    }
+}
+
+/// Validates tool arguments against the tool's input schema without calling
+/// it. Returns the field-level violations, empty when the arguments are valid.
+#[tauri::command]
+pub async fn validate_tool_arguments(
+    request: CallToolRequest,
+    state: State<'_, AppState>,
+) -> Result<Vec<SchemaViolation>, String> {
+    let session = get_session(&state.servers, &request.server_id).await?;
+    match session.handle.validate_tool_arguments(&request.tool_name, request.arguments).await {
+        Ok(_) => Ok(Vec::new()),
+        Err(McpClientError::InvalidToolArguments { violations, .. }) => Ok(violations),
+        Err(e) => {
+            error!("Failed to validate tool arguments: {}", e);
+            Err(format!("Failed to validate tool arguments: {}", e))
+        }
+    }
+}
+
 #[tauri::command]
-pub async fn disconnect_server(state: State<'_, AppState>) -> Result<String, String> {
-    info!("Disconnecting from MCP server");
+pub async fn disconnect_server(server_id: String, state: State<'_, AppState>) -> Result<String, String> {
+    info!("Disconnecting from MCP server '{}'", server_id);
     
-    let mut client = state.mcp_client.lock().await;
-    match client.disconnect().await {
-        Ok(()) => Ok("Disconnected successfully".to_string()),
+    let session = state.servers.remove(&server_id).await
+        .ok_or_else(|| format!("Unknown server: {}", server_id))?;
+    match session.close().await {
+        Ok(Some(stage)) => {
+            info!("Server '{}' {}", server_id, stage);
+            Ok(format!("Disconnected successfully (server {})", stage))
+        }
+        Ok(None) => Ok("Disconnected successfully".to_string()),
         Err(e) => {
             error!("Error during disconnect: {}", e);
             Err(format!("Failed to disconnect cleanly: {}", e))
         }
     }
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub async fn list_resources(
+    server_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<Resource>, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.list_resources().await.map_err(|e| {
+        error!("Failed to list resources: {}", e);
+        format!("Failed to list resources: {}", e)
+    })
+}
+
+/// Returns the resources page at `cursor`; pass the response's `next_cursor`
+/// back to get the next one.
+#[tauri::command]
+pub async fn list_resources_page(
+    server_id: String,
+    cursor: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ListResourcesResponse, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.list_resources_page(cursor).await.map_err(|e| {
+        error!("Failed to list resources: {}", e);
+        format!("Failed to list resources: {}", e)
+    })
+}
+
+#[tauri::command]
+pub async fn list_resource_templates(
+    server_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<ResourceTemplate>, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.list_resource_templates().await.map_err(|e| {
+        error!("Failed to list resource templates: {}", e);
+        format!("Failed to list resource templates: {}", e)
+    })
+}
+
+/// Returns the resource templates page at `cursor`, the paged counterpart of
+/// `list_resource_templates`.
+#[tauri::command]
+pub async fn list_resource_templates_page(
+    server_id: String,
+    cursor: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ListResourceTemplatesResponse, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.list_resource_templates_page(cursor).await.map_err(|e| {
+        error!("Failed to list resource templates: {}", e);
+        format!("Failed to list resource templates: {}", e)
+    })
+}
+
+#[tauri::command]
+pub async fn read_resource(
+    server_id: String,
+    uri: String,
+    state: State<'_, AppState>,
+) -> Result<ReadResourceResponse, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.read_resource(&uri).await.map_err(|e| {
+        error!("Failed to read resource {}: {}", uri, e);
+        format!("Failed to read resource: {}", e)
+    })
+}
+
+/// Expands a resource template with user-supplied values and reads the result.
+#[tauri::command]
+pub async fn read_resource_template(
+    server_id: String,
+    uri_template: String,
+    arguments: HashMap<String, String>,
+    state: State<'_, AppState>,
+) -> Result<ReadResourceResponse, String> {
+    let uri = uri_template::expand(&uri_template, &arguments);
+    read_resource(server_id, uri, state).await
+}
+
+#[tauri::command]
+pub async fn subscribe_resource(
+    server_id: String,
+    uri: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.subscribe_resource(&uri).await.map_err(|e| {
+        error!("Failed to subscribe to resource {}: {}", uri, e);
+        format!("Failed to subscribe to resource: {}", e)
+    })
+}
+
+#[tauri::command]
+pub async fn unsubscribe_resource(
+    server_id: String,
+    uri: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.unsubscribe_resource(&uri).await.map_err(|e| {
+        error!("Failed to unsubscribe from resource {}: {}", uri, e);
+        format!("Failed to unsubscribe from resource: {}", e)
+    })
+}
+
+#[tauri::command]
+pub async fn list_prompts(
+    server_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<Prompt>, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.list_prompts().await.map_err(|e| {
+        error!("Failed to list prompts: {}", e);
+        format!("Failed to list prompts: {}", e)
+    })
+}
+
+/// Returns the prompts page at `cursor`. Fetching the first page resets the
+/// prompts remembered for `get_prompt` argument checks; later pages add to them.
+#[tauri::command]
+pub async fn list_prompts_page(
+    server_id: String,
+    cursor: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ListPromptsResponse, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.list_prompts_page(cursor).await.map_err(|e| {
+        error!("Failed to list prompts: {}", e);
+        format!("Failed to list prompts: {}", e)
+    })
+}
+
+/// Renders a prompt with user-supplied argument values into its message list.
+#[tauri::command]
+pub async fn get_prompt(
+    server_id: String,
+    name: String,
+    arguments: Option<HashMap<String, String>>,
+    state: State<'_, AppState>,
+) -> Result<GetPromptResponse, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    session.handle.get_prompt(&name, arguments.unwrap_or_default()).await.map_err(|e| {
+        error!("Failed to get prompt {}: {}", name, e);
+        format!("Failed to get prompt: {}", e)
+    })
+}
+
+
+/// Approves or rejects a sampling request announced by an
+/// `mcp://sampling-request` event. Approved requests go to the sampling
+/// provider and its message is returned to the server.
+#[tauri::command]
+pub async fn respond_to_sampling(
+    server_id: String,
+    request_id: String,
+    approved: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    let Some(sampling) = &session.sampling else {
+        return Err(format!("Sampling is not enabled for server: {}", server_id));
+    };
+    if sampling.respond(&request_id, approved) {
+        Ok(())
+    } else {
+        Err(format!("No sampling request waiting with id: {}", request_id))
+    }
+}
+
+/// Answers a request for user input announced by an
+/// `mcp://elicitation-request` event. Accepted content that doesn't match
+/// the requested schema is not sent; its violations are returned instead and
+/// the request keeps waiting. An empty list means the answer was sent.
+#[tauri::command]
+pub async fn respond_to_elicitation(
+    server_id: String,
+    request_id: String,
+    action: ElicitAction,
+    content: Option<Value>,
+    state: State<'_, AppState>,
+) -> Result<Vec<SchemaViolation>, String> {
+    let session = get_session(&state.servers, &server_id).await?;
+    match session.elicitation.respond(&request_id, action, content) {
+        Ok(()) => Ok(Vec::new()),
+        Err(ElicitationError::InvalidContent(violations)) => Ok(violations),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn list_roots(server_id: String, state: State<'_, AppState>) -> Result<Vec<Root>, String> {
+    Ok(state.roots.list(&server_id))
+}
+
+/// Exposes a local directory to a server as a root. Roots are kept per
+/// server id, so they can be set up before the server is started.
+#[tauri::command]
+pub async fn add_root(
+    server_id: String,
+    path: String,
+    name: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Root, String> {
+    let root = state.roots.add(&server_id, &path, name).map_err(|e| {
+        error!("Failed to add root {}: {}", path, e);
+        format!("Failed to add root: {}", e)
+    })?;
+    notify_roots_changed(&state.servers, &server_id).await;
+    Ok(root)
+}
+
+#[tauri::command]
+pub async fn remove_root(
+    server_id: String,
+    uri: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let removed = state.roots.remove(&server_id, &uri).map_err(|e| {
+        error!("Failed to remove root {}: {}", uri, e);
+        format!("Failed to remove root: {}", e)
+    })?;
+    if !removed {
+        return Err(format!("Unknown root: {}", uri));
+    }
+    notify_roots_changed(&state.servers, &server_id).await;
+    Ok(())
+}
+
+/// A server that isn't connected gets the new roots when it next asks for them.
+async fn notify_roots_changed(servers: &ServerRegistry, server_id: &str) {
+    let Some(session) = servers.get(server_id).await else {
+        return;
+    };
+    match session.handle.notify_roots_changed().await {
+        Ok(()) | Err(McpClientError::NotConnected) => {}
+        Err(e) => warn!("Failed to notify server '{}' of changed roots: {}", server_id, e),
+    }
+}
@@ -0,0 +1,38 @@
+use crate::infrastructure::events::ClientEvent;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, error, warn};
+
+/// Payload of every MCP event emitted to the webview.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerEvent {
+    pub server_id: String,
+    #[serde(flatten)]
+    pub event: ClientEvent,
+}
+
+/// Emits a server's client events to the webview until the client is dropped.
+pub fn forward_client_events(app: AppHandle, server_id: String, mut events: broadcast::Receiver<ClientEvent>) {
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    let name = event.event_name();
+                    let payload = ServerEvent {
+                        server_id: server_id.clone(),
+                        event,
+                    };
+                    if let Err(e) = app.emit(name, payload) {
+                        error!("Failed to emit {} for server '{}': {}", name, server_id, e);
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Event forwarder for server '{}' skipped {} events", server_id, skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+        debug!("Event forwarder for server '{}' ended", server_id);
+    });
+}
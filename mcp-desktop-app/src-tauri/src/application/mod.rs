@@ -1,2 +1,4 @@
 pub mod commands;
+pub mod events;
+pub mod server_registry;
 pub mod state;
\ No newline at end of file
@@ -0,0 +1,134 @@
+use crate::infrastructure::mcp_transport::{CancellationToken, ServerConfig, ShutdownStage};
+use crate::infrastructure::proper_mcp_client::{McpClientError, McpClientHandle, ProperMcpClient};
+use crate::infrastructure::elicitation::ElicitationHandler;
+use crate::infrastructure::sampling::{SamplingHandler, SamplingProvider};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+
+/// A named connection to one MCP server. Each session owns its own client,
+/// which in turn tracks its own connection state and tool list.
+pub struct ServerSession {
+    pub id: String,
+    pub config: ServerConfig,
+    /// Locked only to connect or disconnect.
+    pub client: Arc<Mutex<ProperMcpClient>>,
+    /// Issues requests without locking `client`, so calls run concurrently.
+    pub handle: McpClientHandle,
+    /// Holds the server's sampling requests until the user decides on them.
+    /// `None` when there is no provider, so sampling isn't advertised.
+    pub sampling: Option<Arc<SamplingHandler>>,
+    /// Holds the server's requests for user input until the user answers.
+    pub elicitation: Arc<ElicitationHandler>,
+    /// In-flight tool calls keyed by the call id the frontend chose.
+    tool_calls: std::sync::Mutex<HashMap<String, CancellationToken>>,
+    /// Cancelled when the session is closed, to abandon a connect in progress.
+    closed: CancellationToken,
+}
+
+impl ServerSession {
+    pub fn new(id: String, config: ServerConfig, sampling_provider: Option<Arc<dyn SamplingProvider>>) -> Self {
+        let client = ProperMcpClient::new();
+        let sampling = sampling_provider.map(|provider| {
+            let sampling = Arc::new(SamplingHandler::new(provider, client.events()));
+            client.request_handlers().register("sampling/createMessage", sampling.clone());
+            sampling
+        });
+        let elicitation = Arc::new(ElicitationHandler::new(client.events()));
+        client.request_handlers().register("elicitation/create", elicitation.clone());
+        Self {
+            id,
+            config,
+            handle: client.handle(),
+            sampling,
+            elicitation,
+            client: Arc::new(Mutex::new(client)),
+            tool_calls: std::sync::Mutex::new(HashMap::new()),
+            closed: CancellationToken::new(),
+        }
+    }
+
+    /// Connects the client to the configured server, giving up if the
+    /// session is closed before the connection is established.
+    pub async fn connect(&self) -> Result<(), McpClientError> {
+        let mut client = self.client.lock().await;
+        let reason = tokio::select! {
+            result = client.connect(self.config.clone()) => return result,
+            reason = self.closed.cancelled() => reason,
+        };
+        // The abandoned connect may have left a transport running
+        let _ = client.disconnect().await;
+        Err(McpClientError::Cancelled(reason))
+    }
+
+    /// Disconnects the client, first abandoning a connect still in progress.
+    pub async fn close(&self) -> Result<Option<ShutdownStage>, McpClientError> {
+        self.closed.cancel("Server session closed");
+        self.client.lock().await.disconnect().await
+    }
+
+    /// Registers a tool call and returns the token that cancels it.
+    pub fn begin_tool_call(&self, call_id: &str) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.tool_calls.lock().unwrap().insert(call_id.to_string(), token.clone());
+        token
+    }
+
+    pub fn end_tool_call(&self, call_id: &str) {
+        self.tool_calls.lock().unwrap().remove(call_id);
+    }
+
+    /// Cancels a tool call, returning false if no such call is in flight.
+    pub fn cancel_tool_call(&self, call_id: &str, reason: &str) -> bool {
+        match self.tool_calls.lock().unwrap().get(call_id) {
+            Some(token) => {
+                token.cancel(reason);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Registry of all server sessions keyed by server id.
+pub struct ServerRegistry {
+    sessions: RwLock<HashMap<String, Arc<ServerSession>>>,
+}
+
+impl ServerRegistry {
+    pub fn new() -> Self {
+        Self {
+            sessions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get(&self, id: &str) -> Option<Arc<ServerSession>> {
+        self.sessions.read().await.get(id).cloned()
+    }
+
+    /// Inserts a session, returning the session previously registered under the same id.
+    pub async fn insert(&self, session: Arc<ServerSession>) -> Option<Arc<ServerSession>> {
+        let mut sessions = self.sessions.write().await;
+        sessions.insert(session.id.clone(), session)
+    }
+
+    pub async fn remove(&self, id: &str) -> Option<Arc<ServerSession>> {
+        self.sessions.write().await.remove(id)
+    }
+
+    /// Removes `session` unless another session has replaced it under its id.
+    pub async fn remove_session(&self, session: &Arc<ServerSession>) {
+        let mut sessions = self.sessions.write().await;
+        if sessions.get(&session.id).is_some_and(|current| Arc::ptr_eq(current, session)) {
+            sessions.remove(&session.id);
+        }
+    }
+
+    /// Returns all sessions ordered by server id.
+    pub async fn list(&self) -> Vec<Arc<ServerSession>> {
+        let sessions = self.sessions.read().await;
+        let mut list: Vec<_> = sessions.values().cloned().collect();
+        list.sort_by(|a, b| a.id.cmp(&b.id));
+        list
+    }
+}
@@ -1,15 +1,31 @@
-use crate::infrastructure::proper_mcp_client::ProperMcpClient;
+use crate::application::server_registry::ServerRegistry;
+use crate::infrastructure::roots::RootsManager;
+use crate::infrastructure::sampling::{MockSamplingProvider, SamplingProvider, SamplingProviderKind};
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::Mutex;
 
 pub struct AppState {
-    pub mcp_client: Arc<Mutex<ProperMcpClient>>,
+    pub servers: ServerRegistry,
+    pub roots: Arc<RootsManager>,
+    /// The sampling providers servers can opt into when they are started.
+    sampling_providers: HashMap<SamplingProviderKind, Arc<dyn SamplingProvider>>,
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    /// Creates the state, loading saved settings from `config_dir`.
+    pub fn new(config_dir: &Path) -> Self {
         Self {
-            mcp_client: Arc::new(Mutex::new(ProperMcpClient::new())),
+            servers: ServerRegistry::new(),
+            roots: Arc::new(RootsManager::load(config_dir.join("roots.json"))),
+            sampling_providers: HashMap::from([(
+                SamplingProviderKind::Mock,
+                Arc::new(MockSamplingProvider) as Arc<dyn SamplingProvider>,
+            )]),
         }
     }
+
+    pub fn sampling_provider(&self, kind: SamplingProviderKind) -> Option<Arc<dyn SamplingProvider>> {
+        self.sampling_providers.get(&kind).cloned()
+    }
 }
\ No newline at end of file
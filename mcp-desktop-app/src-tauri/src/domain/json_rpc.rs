@@ -1,6 +1,5 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -45,34 +44,13 @@ pub struct JsonRpcError {
     pub data: Option<Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum JsonRpcId {
     String(String),
     Number(i64),
 }
 
-impl JsonRpcRequest {
-    pub fn new(method: String, params: Option<Value>) -> Self {
-        Self {
-            jsonrpc: "2.0".to_string(),
-            method,
-            params,
-            id: JsonRpcId::String(Uuid::new_v4().to_string()),
-        }
-    }
-}
-
-impl JsonRpcNotification {
-    pub fn new(method: String, params: Option<Value>) -> Self {
-        Self {
-            jsonrpc: "2.0".to_string(),
-            method,
-            params,
-        }
-    }
-}
-
 impl JsonRpcResponse {
     pub fn success(id: JsonRpcId, result: Value) -> Self {
         Self {
@@ -91,4 +69,30 @@ impl JsonRpcResponse {
             id,
         }
     }
-}
\ No newline at end of file
+}
+
+impl JsonRpcError {
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    pub const INVALID_PARAMS: i32 = -32602;
+    pub const INTERNAL_ERROR: i32 = -32603;
+
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(Self::METHOD_NOT_FOUND, format!("Method not found: {}", method))
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(Self::INVALID_PARAMS, message)
+    }
+
+    pub fn internal_error(message: impl Into<String>) -> Self {
+        Self::new(Self::INTERNAL_ERROR, message)
+    }
+}
@@ -0,0 +1,159 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// One way a JSON value fails to match a schema.
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value, empty for the value itself.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Joins violations into a single line for error messages.
+pub fn describe(violations: &[SchemaViolation]) -> String {
+    violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+/// Fills in `default` values for properties missing from `instance`,
+/// descending into nested objects and array items. Values that are present,
+/// even if null, are left alone.
+pub fn apply_defaults(schema: &Value, instance: &mut Value) {
+    match instance {
+        Value::Object(object) => {
+            let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+                return;
+            };
+            for (name, property) in properties {
+                if !object.contains_key(name) {
+                    if let Some(default) = property.get("default") {
+                        object.insert(name.clone(), default.clone());
+                    }
+                }
+                if let Some(value) = object.get_mut(name) {
+                    apply_defaults(property, value);
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items").filter(|items| items.is_object()) {
+                for item in items {
+                    apply_defaults(item_schema, item);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Why a value could not be shown to match a schema.
+#[derive(Debug)]
+pub enum ValidationError {
+    /// The value doesn't match the schema.
+    Violations(Vec<SchemaViolation>),
+    /// The schema can't be compiled, for example because it refers to an
+    /// unknown meta-schema or an external `$ref`, so nothing was checked.
+    UnusableSchema(String),
+}
+
+/// Validates `instance` against a JSON Schema, returning every violation found.
+pub fn validate(schema: &Value, instance: &Value) -> Result<(), ValidationError> {
+    let validator = jsonschema::options()
+        .should_validate_formats(true)
+        .build(schema)
+        .map_err(|e| ValidationError::UnusableSchema(e.to_string()))?;
+
+    let violations: Vec<SchemaViolation> = validator.iter_errors(instance)
+        .map(|error| SchemaViolation {
+            path: error.instance_path.to_string(),
+            message: error.to_string(),
+        })
+        .collect();
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError::Violations(violations))
+    }
+}
+
+/// Checks that a schema describes an object whose properties are all
+/// strings, numbers, integers or booleans, the only shape servers may ask
+/// users to fill in.
+pub fn check_flat_object(schema: &Value) -> Result<(), Vec<SchemaViolation>> {
+    let violation = |path: String, message: &str| SchemaViolation {
+        path,
+        message: message.to_string(),
+    };
+
+    if schema.get("type").and_then(Value::as_str) != Some("object") {
+        return Err(vec![violation(String::new(), "Schema must have type \"object\"")]);
+    }
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Err(vec![violation("/properties".to_string(), "Schema must have an object of properties")]);
+    };
+
+    let violations: Vec<SchemaViolation> = properties.iter()
+        .filter(|(_, property)| !matches!(
+            property.get("type").and_then(Value::as_str),
+            Some("string" | "number" | "integer" | "boolean")
+        ))
+        .map(|(name, _)| violation(
+            format!("/properties/{}", name),
+            "Property must have type string, number, integer or boolean",
+        ))
+        .collect();
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reports_violations_by_path() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "count": { "type": "integer" } },
+            "required": ["name"],
+        });
+
+        match validate(&schema, &json!({ "count": "many" })) {
+            Err(ValidationError::Violations(violations)) => {
+                let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+                assert!(paths.contains(&""));
+                assert!(paths.contains(&"/count"));
+            }
+            other => panic!("expected violations, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn schema_that_does_not_compile_is_unusable() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "path": { "$ref": "https://example.com/schemas/path.json" } },
+        });
+
+        assert!(matches!(
+            validate(&schema, &json!({ "path": "/tmp" })),
+            Err(ValidationError::UnusableSchema(_))
+        ));
+    }
+}
@@ -1,2 +1,6 @@
 pub mod mcp_types;
-pub mod json_rpc;
\ No newline at end of file
+pub mod json_rpc;
+pub mod notifications;
+pub mod uri_template;
+pub mod json_schema;
+pub mod protocol_version;
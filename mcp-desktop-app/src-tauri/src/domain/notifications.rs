@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::domain::json_rpc::{JsonRpcId, JsonRpcNotification};
+use crate::domain::mcp_types::LoggingLevel;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceUpdatedParams {
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingMessageParams {
+    pub level: LoggingLevel,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    pub data: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressParams {
+    #[serde(rename = "progressToken")]
+    pub progress_token: Value,
+    pub progress: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelledParams {
+    #[serde(rename = "requestId")]
+    pub request_id: JsonRpcId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// A notification sent by the server, parsed according to its method.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerNotification {
+    ToolListChanged,
+    ResourceListChanged,
+    ResourceUpdated(ResourceUpdatedParams),
+    PromptListChanged,
+    LoggingMessage(LoggingMessageParams),
+    Progress(ProgressParams),
+    Cancelled(CancelledParams),
+    /// Unknown methods, or known ones whose params failed to parse.
+    Other {
+        method: String,
+        params: Option<Value>,
+    },
+}
+
+impl ServerNotification {
+    pub fn method(&self) -> &str {
+        match self {
+            ServerNotification::ToolListChanged => "notifications/tools/list_changed",
+            ServerNotification::ResourceListChanged => "notifications/resources/list_changed",
+            ServerNotification::ResourceUpdated(_) => "notifications/resources/updated",
+            ServerNotification::PromptListChanged => "notifications/prompts/list_changed",
+            ServerNotification::LoggingMessage(_) => "notifications/message",
+            ServerNotification::Progress(_) => "notifications/progress",
+            ServerNotification::Cancelled(_) => "notifications/cancelled",
+            ServerNotification::Other { method, .. } => method,
+        }
+    }
+}
+
+impl From<JsonRpcNotification> for ServerNotification {
+    fn from(notification: JsonRpcNotification) -> Self {
+        fn parse<T: serde::de::DeserializeOwned>(params: &Option<Value>) -> Option<T> {
+            params.clone().and_then(|p| serde_json::from_value(p).ok())
+        }
+
+        let params = &notification.params;
+        let parsed = match notification.method.as_str() {
+            "notifications/tools/list_changed" => Some(ServerNotification::ToolListChanged),
+            "notifications/resources/list_changed" => Some(ServerNotification::ResourceListChanged),
+            "notifications/resources/updated" => parse(params).map(ServerNotification::ResourceUpdated),
+            "notifications/prompts/list_changed" => Some(ServerNotification::PromptListChanged),
+            "notifications/message" => parse(params).map(ServerNotification::LoggingMessage),
+            "notifications/progress" => parse(params).map(ServerNotification::Progress),
+            "notifications/cancelled" => parse(params).map(ServerNotification::Cancelled),
+            _ => None,
+        };
+
+        parsed.unwrap_or(ServerNotification::Other {
+            method: notification.method,
+            params: notification.params,
+        })
+    }
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+
+/// An MCP protocol revision this client can speak. Later revisions compare
+/// greater, so features can be gated with `>=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProtocolVersion {
+    /// Pre-release revision some early servers still answer with.
+    V2024_10_07,
+    V2024_11_05,
+    V2025_03_26,
+    V2025_06_18,
+}
+
+impl ProtocolVersion {
+    /// The revision requested in `initialize`.
+    pub const LATEST: ProtocolVersion = ProtocolVersion::V2025_06_18;
+
+    /// Every supported revision, newest first.
+    pub const SUPPORTED: [ProtocolVersion; 4] = [
+        ProtocolVersion::V2025_06_18,
+        ProtocolVersion::V2025_03_26,
+        ProtocolVersion::V2024_11_05,
+        ProtocolVersion::V2024_10_07,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProtocolVersion::V2024_10_07 => "2024-10-07",
+            ProtocolVersion::V2024_11_05 => "2024-11-05",
+            ProtocolVersion::V2025_03_26 => "2025-03-26",
+            ProtocolVersion::V2025_06_18 => "2025-06-18",
+        }
+    }
+
+    /// Picks the revision to use from the one the server answered with.
+    /// Servers answer with the requested revision when they support it and
+    /// with another they do support otherwise; that one is accepted if it's
+    /// ours too. `None` means there is no common revision.
+    pub fn negotiate(server_version: &str) -> Option<ProtocolVersion> {
+        Self::SUPPORTED.into_iter().find(|version| version.as_str() == server_version)
+    }
+
+    /// Name of the notification that completes initialization.
+    pub fn initialized_notification(self) -> &'static str {
+        if self >= ProtocolVersion::V2024_11_05 {
+            "notifications/initialized"
+        } else {
+            "initialized"
+        }
+    }
+
+    /// Whether tools may declare `outputSchema` and return `structuredContent`.
+    pub fn supports_structured_content(self) -> bool {
+        self >= ProtocolVersion::V2025_06_18
+    }
+
+    /// Whether tool results may contain `resource_link` content.
+    pub fn supports_resource_links(self) -> bool {
+        self >= ProtocolVersion::V2025_06_18
+    }
+
+    /// Whether servers may send `elicitation/create` requests.
+    pub fn supports_elicitation(self) -> bool {
+        self >= ProtocolVersion::V2025_06_18
+    }
+
+    /// Whether the server may send a request with this method.
+    pub fn allows_server_request(self, method: &str) -> bool {
+        match method {
+            "elicitation/create" => self.supports_elicitation(),
+            _ => true,
+        }
+    }
+
+    /// Whether HTTP requests must carry the `MCP-Protocol-Version` header.
+    pub fn requires_version_header(self) -> bool {
+        self >= ProtocolVersion::V2025_06_18
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
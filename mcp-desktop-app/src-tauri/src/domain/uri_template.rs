@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+/// Expands an RFC 6570 URI template such as `file:///{path}` or
+/// `db://{table}{?limit,offset}`. All operators are supported; prefix and
+/// explode modifiers are accepted but ignored. Undefined variables expand to nothing.
+pub fn expand(template: &str, variables: &HashMap<String, String>) -> String {
+    let mut result = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            // Unterminated expression, keep it literally
+            result.push_str(&rest[start..]);
+            return result;
+        };
+        result.push_str(&expand_expression(&rest[start + 1..start + len], variables));
+        rest = &rest[start + len + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+fn expand_expression(expression: &str, variables: &HashMap<String, String>) -> String {
+    let (operator, names) = match expression.chars().next() {
+        Some(op @ ('+' | '#' | '.' | '/' | ';' | '?' | '&')) => (Some(op), &expression[1..]),
+        _ => (None, expression),
+    };
+
+    // (prefix, separator, named, allow reserved characters)
+    let (prefix, separator, named, allow_reserved) = match operator {
+        Some('+') => ("", ",", false, true),
+        Some('#') => ("#", ",", false, true),
+        Some('.') => (".", ".", false, false),
+        Some('/') => ("/", "/", false, false),
+        Some(';') => (";", ";", true, false),
+        Some('?') => ("?", "&", true, false),
+        Some('&') => ("&", "&", true, false),
+        _ => ("", ",", false, false),
+    };
+
+    let parts: Vec<String> = names.split(',')
+        .filter_map(|spec| {
+            let name = spec.trim_end_matches('*').split(':').next().unwrap_or_default();
+            let value = variables.get(name)?;
+            let encoded = encode(value, allow_reserved);
+            Some(match (named, operator) {
+                (true, Some(';')) if value.is_empty() => name.to_string(),
+                (true, _) => format!("{}={}", name, encoded),
+                (false, _) => encoded,
+            })
+        })
+        .collect();
+
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{}{}", prefix, parts.join(separator))
+    }
+}
+
+fn encode(value: &str, allow_reserved: bool) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        let c = byte as char;
+        let unreserved = c.is_ascii_alphanumeric() || "-._~".contains(c);
+        let reserved = ":/?#[]@!$&'()*+,;=".contains(c);
+        if unreserved || (allow_reserved && reserved) {
+            encoded.push(c);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
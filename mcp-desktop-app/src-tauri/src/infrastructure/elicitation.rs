@@ -0,0 +1,198 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::domain::json_rpc::JsonRpcError;
+use crate::domain::json_schema::{self, SchemaViolation, ValidationError};
+use crate::domain::mcp_types::{ElicitAction, ElicitRequest, ElicitResult};
+use crate::infrastructure::events::{ClientEvent, ElicitationRequest, EventBus};
+use crate::infrastructure::request_handlers::{PendingGuard, RequestHandler};
+
+#[derive(Error, Debug)]
+pub enum ElicitationError {
+    #[error("No elicitation request waiting with id: {0}")]
+    UnknownRequest(String),
+    #[error("Invalid content: {}", json_schema::describe(.0))]
+    InvalidContent(Vec<SchemaViolation>),
+}
+
+/// How long a request waits for the user before it is cancelled.
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+struct PendingElicitation {
+    schema: Value,
+    reply: oneshot::Sender<ElicitResult>,
+}
+
+/// Answers `elicitation/create`. Each request is announced as
+/// `ClientEvent::ElicitationRequested` and waits for the user's answer
+/// through `respond`; the server only ever receives content that matches
+/// the schema it asked for. Requests the user doesn't answer within
+/// `ANSWER_TIMEOUT` are cancelled.
+pub struct ElicitationHandler {
+    events: EventBus,
+    pending: Mutex<HashMap<String, PendingElicitation>>,
+}
+
+impl ElicitationHandler {
+    pub fn new(events: EventBus) -> Self {
+        Self {
+            events,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Answers a pending request. Accepted content gets the schema's defaults
+    /// filled in and must then match the schema, otherwise the request keeps
+    /// waiting so the user can correct it. Content is dropped for the other
+    /// actions.
+    pub fn respond(&self, request_id: &str, action: ElicitAction, content: Option<Value>) -> Result<(), ElicitationError> {
+        let mut pending = self.pending.lock().unwrap();
+        let Some(request) = pending.remove(request_id) else {
+            return Err(ElicitationError::UnknownRequest(request_id.to_string()));
+        };
+
+        let content = match action {
+            ElicitAction::Accept => {
+                let mut content = content.unwrap_or_else(|| json!({}));
+                json_schema::apply_defaults(&request.schema, &mut content);
+                match json_schema::validate(&request.schema, &content) {
+                    Ok(()) => {}
+                    Err(ValidationError::Violations(violations)) => {
+                        pending.insert(request_id.to_string(), request);
+                        return Err(ElicitationError::InvalidContent(violations));
+                    }
+                    Err(ValidationError::UnusableSchema(e)) => {
+                        warn!("Not checking content for elicitation request {}, its schema can't be used: {}", request_id, e);
+                    }
+                }
+                Some(content)
+            }
+            ElicitAction::Decline | ElicitAction::Cancel => None,
+        };
+
+        request.reply.send(ElicitResult { action, content })
+            .map_err(|_| ElicitationError::UnknownRequest(request_id.to_string()))
+    }
+}
+
+#[async_trait]
+impl RequestHandler for ElicitationHandler {
+    async fn handle(&self, params: Option<Value>) -> Result<Value, JsonRpcError> {
+        let request: ElicitRequest = serde_json::from_value(params.unwrap_or(Value::Null))
+            .map_err(|e| JsonRpcError::invalid_params(format!("Invalid elicitation request: {}", e)))?;
+        json_schema::check_flat_object(&request.requested_schema).map_err(|violations| {
+            JsonRpcError::invalid_params(format!("Invalid requested schema: {}", json_schema::describe(&violations)))
+        })?;
+
+        let request_id = Uuid::new_v4().to_string();
+        let (reply, answer) = oneshot::channel();
+        self.pending.lock().unwrap().insert(request_id.clone(), PendingElicitation {
+            schema: request.requested_schema.clone(),
+            reply,
+        });
+        let _pending = PendingGuard::new(&self.pending, &request_id);
+        self.events.publish(ClientEvent::ElicitationRequested(ElicitationRequest {
+            request_id: request_id.clone(),
+            request,
+        }));
+
+        // A dropped reply means the prompt went away without an answer
+        let result = match tokio::time::timeout(ANSWER_TIMEOUT, answer).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) | Err(_) => ElicitResult {
+                action: ElicitAction::Cancel,
+                content: None,
+            },
+        };
+        info!("Elicitation request {} answered with {:?}", request_id, result.action);
+        Ok(json!(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::task::JoinHandle;
+
+    /// Asks for a branch name and an optional depth, returning the id the
+    /// user answers with and the task that resolves to the server's answer.
+    async fn ask_for_branch(handler: &Arc<ElicitationHandler>, events: &EventBus) -> (String, JoinHandle<Value>) {
+        let mut subscription = events.subscribe();
+        let answer = tokio::spawn({
+            let handler = handler.clone();
+            async move {
+                handler.handle(Some(json!({
+                    "message": "Which branch?",
+                    "requestedSchema": {
+                        "type": "object",
+                        "properties": {
+                            "branch": { "type": "string" },
+                            "depth": { "type": "integer", "default": 1 },
+                        },
+                        "required": ["branch"],
+                    },
+                }))).await.unwrap()
+            }
+        });
+        let ClientEvent::ElicitationRequested(elicitation) = subscription.recv().await.unwrap() else {
+            panic!("expected an elicitation request event");
+        };
+        (elicitation.request_id, answer)
+    }
+
+    fn handler() -> (Arc<ElicitationHandler>, EventBus) {
+        let events = EventBus::new();
+        (Arc::new(ElicitationHandler::new(events.clone())), events)
+    }
+
+    #[tokio::test]
+    async fn accepted_content_is_sent_with_defaults() {
+        let (handler, events) = handler();
+        let (request_id, answer) = ask_for_branch(&handler, &events).await;
+
+        handler.respond(&request_id, ElicitAction::Accept, Some(json!({ "branch": "main" }))).unwrap();
+        assert_eq!(answer.await.unwrap(), json!({ "action": "accept", "content": { "branch": "main", "depth": 1 } }));
+    }
+
+    #[tokio::test]
+    async fn invalid_content_keeps_the_request_waiting() {
+        let (handler, events) = handler();
+        let (request_id, answer) = ask_for_branch(&handler, &events).await;
+
+        match handler.respond(&request_id, ElicitAction::Accept, Some(json!({ "depth": "deep" }))) {
+            Err(ElicitationError::InvalidContent(violations)) => {
+                let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+                assert!(paths.contains(&""), "missing branch not reported: {:?}", violations);
+                assert!(paths.contains(&"/depth"), "wrong depth type not reported: {:?}", violations);
+            }
+            other => panic!("expected invalid content, got {:?}", other),
+        }
+        assert!(!answer.is_finished());
+
+        handler.respond(&request_id, ElicitAction::Accept, Some(json!({ "branch": "dev", "depth": 3 }))).unwrap();
+        assert_eq!(answer.await.unwrap(), json!({ "action": "accept", "content": { "branch": "dev", "depth": 3 } }));
+    }
+
+    #[tokio::test]
+    async fn declined_and_cancelled_requests_send_no_content() {
+        let (handler, events) = handler();
+        for (action, name) in [(ElicitAction::Decline, "decline"), (ElicitAction::Cancel, "cancel")] {
+            let (request_id, answer) = ask_for_branch(&handler, &events).await;
+
+            handler.respond(&request_id, action, Some(json!({ "branch": "main" }))).unwrap();
+            assert_eq!(answer.await.unwrap(), json!({ "action": name }));
+            assert!(matches!(
+                handler.respond(&request_id, action, None),
+                Err(ElicitationError::UnknownRequest(_))
+            ));
+        }
+    }
+}
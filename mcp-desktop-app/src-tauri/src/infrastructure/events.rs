@@ -0,0 +1,94 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::domain::mcp_types::{CreateMessageRequest, ElicitRequest, ResourceContents, ToolListDiff};
+use crate::domain::notifications::ServerNotification;
+use crate::infrastructure::proper_mcp_client::ClientState;
+use crate::infrastructure::stderr_log::StderrLine;
+
+const EVENT_BUS_CAPACITY: usize = 256;
+
+/// Everything a client reports to its subscribers: server notifications plus
+/// changes the client observes itself.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+pub enum ClientEvent {
+    ConnectionState(ClientState),
+    Notification(ServerNotification),
+    ToolsChanged(ToolListDiff),
+    ResourceUpdated(ResourceUpdate),
+    ToolProgress(ToolProgress),
+    Stderr(StderrLine),
+    SamplingRequested(SamplingRequest),
+    ElicitationRequested(ElicitationRequest),
+}
+
+/// Fresh contents of a subscribed resource the server reported as changed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceUpdate {
+    pub uri: String,
+    pub contents: Vec<ResourceContents>,
+}
+
+/// Progress reported by the server for a tool call made with a progress token.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolProgress {
+    pub progress_token: String,
+    pub tool: String,
+    pub progress: f64,
+    pub total: Option<f64>,
+    pub message: Option<String>,
+}
+
+/// A `sampling/createMessage` request waiting for the user's approval.
+#[derive(Debug, Clone, Serialize)]
+pub struct SamplingRequest {
+    pub request_id: String,
+    pub request: CreateMessageRequest,
+}
+
+/// An `elicitation/create` request waiting for the user's input.
+#[derive(Debug, Clone, Serialize)]
+pub struct ElicitationRequest {
+    pub request_id: String,
+    pub request: ElicitRequest,
+}
+
+impl ClientEvent {
+    /// Name of the Tauri event this is emitted under.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            ClientEvent::ConnectionState(_) => "mcp://connection-state",
+            ClientEvent::Notification(_) => "mcp://notification",
+            ClientEvent::ToolsChanged(_) => "mcp://tools-changed",
+            ClientEvent::ResourceUpdated(_) => "mcp://resource-updated",
+            ClientEvent::ToolProgress(_) => "mcp://tool-progress",
+            ClientEvent::Stderr(_) => "mcp://server-stderr",
+            ClientEvent::SamplingRequested(_) => "mcp://sampling-request",
+            ClientEvent::ElicitationRequested(_) => "mcp://elicitation-request",
+        }
+    }
+}
+
+/// Broadcasts client events to any number of subscribers. Publishing never
+/// blocks; slow subscribers miss the oldest events.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ClientEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: ClientEvent) {
+        // An error only means nobody is subscribed right now
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ClientEvent> {
+        self.sender.subscribe()
+    }
+}
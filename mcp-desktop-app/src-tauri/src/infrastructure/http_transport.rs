@@ -0,0 +1,548 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::domain::json_rpc::{JsonRpcId, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest};
+use crate::domain::protocol_version::ProtocolVersion;
+use crate::infrastructure::mcp_transport::{
+    await_response, route_request, InboundMessage, InboundSender, PendingRequests, RequestOptions, ShutdownStage,
+    TimeoutConfig, Transport, TransportError,
+};
+use crate::infrastructure::sse::{SseDecoder, SseEvent};
+
+const SESSION_ID_HEADER: &str = "mcp-session-id";
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// Converts user-configured headers (e.g. `Authorization`) into a `HeaderMap`.
+pub(crate) fn build_header_map(headers: HashMap<String, String>) -> Result<HeaderMap, TransportError> {
+    let mut header_map = HeaderMap::new();
+    for (key, value) in headers {
+        let name = HeaderName::from_bytes(key.as_bytes())
+            .map_err(|e| TransportError::Http(format!("Invalid header name '{}': {}", key, e)))?;
+        let value = HeaderValue::from_str(&value)
+            .map_err(|e| TransportError::Http(format!("Invalid value for header '{}': {}", key, e)))?;
+        header_map.insert(name, value);
+    }
+    Ok(header_map)
+}
+
+/// MCP Streamable HTTP transport. Every client message is POSTed to a single
+/// endpoint; the server answers with a JSON body or an SSE stream, and may push
+/// its own messages over a long-lived GET stream.
+pub struct StreamableHttpTransport {
+    shared: Arc<HttpShared>,
+    listener: Mutex<Option<JoinHandle<()>>>,
+}
+
+struct HttpShared {
+    client: reqwest::Client,
+    url: String,
+    headers: HeaderMap,
+    session_id: RwLock<Option<String>>,
+    protocol_version: RwLock<Option<ProtocolVersion>>,
+    pending: PendingRequests,
+    timeouts: TimeoutConfig,
+    inbound: InboundSender,
+}
+
+impl StreamableHttpTransport {
+    pub fn new(
+        url: String,
+        headers: HashMap<String, String>,
+        timeouts: TimeoutConfig,
+        inbound: InboundSender,
+    ) -> Result<Self, TransportError> {
+        info!("Using Streamable HTTP transport: {}", url);
+
+        Ok(Self {
+            shared: Arc::new(HttpShared {
+                client: reqwest::Client::new(),
+                url,
+                headers: build_header_map(headers)?,
+                session_id: RwLock::new(None),
+                protocol_version: RwLock::new(None),
+                pending: PendingRequests::default(),
+                timeouts,
+                inbound,
+            }),
+            listener: Mutex::new(None),
+        })
+    }
+
+    /// Opens the GET stream for server-initiated messages once the session is
+    /// initialized. The first notification a client sends is `initialized`,
+    /// so this is called after every successful notification. A stream that
+    /// ended, such as one bound to an expired session, is opened again.
+    fn ensure_listener(&self) {
+        let mut listener = self.listener.lock().unwrap();
+        if listener.as_ref().is_none_or(|listener| listener.is_finished()) {
+            let shared = self.shared.clone();
+            *listener = Some(tokio::spawn(async move { shared.listen().await }));
+        }
+    }
+}
+
+impl HttpShared {
+    fn request_headers(&self) -> HeaderMap {
+        let mut headers = self.headers.clone();
+        if let Some(ref session_id) = *self.session_id.read().unwrap() {
+            if let Ok(value) = HeaderValue::from_str(session_id) {
+                headers.insert(SESSION_ID_HEADER, value);
+            }
+        }
+        if let Some(version) = *self.protocol_version.read().unwrap() {
+            headers.insert(PROTOCOL_VERSION_HEADER, HeaderValue::from_static(version.as_str()));
+        }
+        headers
+    }
+
+    async fn post(&self, message: &JsonRpcMessage) -> Result<reqwest::Response, TransportError> {
+        debug!("POST {}: {}", self.url, serde_json::to_string(message).unwrap_or_default());
+
+        let response = self.client.post(&self.url)
+            .headers(self.request_headers())
+            .header(ACCEPT, "application/json, text/event-stream")
+            .json(message)
+            .send()
+            .await?;
+
+        if let Some(session_id) = response.headers().get(SESSION_ID_HEADER).and_then(|v| v.to_str().ok()) {
+            let mut current = self.session_id.write().unwrap();
+            if current.as_deref() != Some(session_id) {
+                info!("Server assigned session ID: {}", session_id);
+                *current = Some(session_id.to_string());
+            }
+        }
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        if status == StatusCode::NOT_FOUND && self.expire_session() {
+            return Err(TransportError::Http("Session expired, the server no longer recognizes the session ID".into()));
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(TransportError::Http(format!("Server returned {}: {}", status, body)))
+    }
+
+    /// Forgets a session the server answered 404 for and asks the client to
+    /// initialize a new one. Returns false if there was no session, so
+    /// requests failing together report the expiry only once.
+    fn expire_session(&self) -> bool {
+        let Some(session_id) = self.session_id.write().unwrap().take() else {
+            return false;
+        };
+        warn!("Session {} expired", session_id);
+        *self.protocol_version.write().unwrap() = None;
+        if self.inbound.send(InboundMessage::SessionLost("HTTP session expired".into())).is_err() {
+            warn!("Client is no longer listening for session loss");
+        }
+        true
+    }
+
+    /// Reads a response body, dispatching every JSON-RPC message it carries.
+    async fn consume_body(self: &Arc<Self>, response: reqwest::Response) -> Result<(), TransportError> {
+        if response.status() == StatusCode::ACCEPTED {
+            return Ok(());
+        }
+
+        let content_type = response.headers().get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        if content_type.starts_with("text/event-stream") {
+            let mut last_event_id = None;
+            return self.consume_event_stream(response, &mut last_event_id).await;
+        }
+
+        let body = response.bytes().await?;
+        if body.iter().all(|b| b.is_ascii_whitespace()) {
+            return Ok(());
+        }
+        if !content_type.starts_with("application/json") {
+            return Err(TransportError::Http(format!("Unexpected content type: {}", content_type)));
+        }
+
+        // A body may carry a single message or a batch
+        match serde_json::from_slice::<Value>(&body)? {
+            Value::Array(items) => {
+                for item in items {
+                    self.dispatch(serde_json::from_value(item)?);
+                }
+            }
+            value => self.dispatch(serde_json::from_value(value)?),
+        }
+        Ok(())
+    }
+
+    async fn consume_event_stream(
+        self: &Arc<Self>,
+        response: reqwest::Response,
+        last_event_id: &mut Option<String>,
+    ) -> Result<(), TransportError> {
+        let mut decoder = SseDecoder::new();
+        let mut stream = response.bytes_stream();
+
+        while let Some(chunk) = stream.next().await {
+            for event in decoder.feed(&chunk?) {
+                self.handle_event(event, last_event_id);
+            }
+        }
+        if let Some(event) = decoder.finish() {
+            self.handle_event(event, last_event_id);
+        }
+        Ok(())
+    }
+
+    fn handle_event(self: &Arc<Self>, event: SseEvent, last_event_id: &mut Option<String>) {
+        if event.id.is_some() {
+            *last_event_id = event.id.clone();
+        }
+        if event.event_type() != "message" {
+            debug!("Ignoring SSE event of type '{}'", event.event_type());
+            return;
+        }
+
+        debug!("Received: {}", event.data);
+        match serde_json::from_str::<JsonRpcMessage>(&event.data) {
+            Ok(message) => self.dispatch(message),
+            Err(e) => error!("Failed to parse JSON-RPC message: {} - Raw data: {}", e, event.data),
+        }
+    }
+
+    fn dispatch(self: &Arc<Self>, message: JsonRpcMessage) {
+        match message {
+            JsonRpcMessage::Response(response) => {
+                info!("Received response for request ID: {:?}", response.id);
+                self.pending.complete(response);
+            }
+            JsonRpcMessage::Notification(notification) => {
+                debug!("Received notification: {}", notification.method);
+                if self.inbound.send(InboundMessage::Notification(notification)).is_err() {
+                    warn!("Dropping notification, client is no longer listening");
+                }
+            }
+            JsonRpcMessage::Request(request) => {
+                info!("Received request from server: {} with ID: {:?}", request.method, request.id);
+                let shared = self.clone();
+                tokio::spawn(async move {
+                    let Some(response) = route_request(shared.inbound.clone(), request).await else {
+                        return;
+                    };
+                    if let Err(e) = shared.post(&JsonRpcMessage::Response(response)).await {
+                        error!("Failed to send response to server request: {}", e);
+                    }
+                });
+            }
+        }
+    }
+
+    /// Keeps a GET stream open for server-initiated messages, resuming from the
+    /// last seen event ID whenever the server closes it.
+    async fn listen(self: &Arc<Self>) {
+        let mut last_event_id: Option<String> = None;
+
+        loop {
+            let mut request = self.client.get(&self.url)
+                .headers(self.request_headers())
+                .header(ACCEPT, "text/event-stream");
+            if let Some(ref id) = last_event_id {
+                request = request.header(LAST_EVENT_ID_HEADER, id);
+            }
+
+            match request.send().await {
+                Ok(response) if response.status() == StatusCode::METHOD_NOT_ALLOWED => {
+                    info!("Server does not offer a GET stream for server-initiated messages");
+                    return;
+                }
+                Ok(response) if response.status() == StatusCode::NOT_FOUND => {
+                    self.expire_session();
+                    return;
+                }
+                Ok(response) if response.status().is_success() => {
+                    debug!("GET stream opened");
+                    if let Err(e) = self.consume_event_stream(response, &mut last_event_id).await {
+                        warn!("GET stream failed: {}", e);
+                    }
+                    debug!("GET stream closed, reconnecting");
+                }
+                Ok(response) => {
+                    warn!("GET stream rejected with status {}", response.status());
+                    return;
+                }
+                Err(e) => {
+                    warn!("Failed to open GET stream: {}", e);
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for StreamableHttpTransport {
+    async fn send_request_with(&self, method: &str, params: Option<Value>, options: &RequestOptions) -> Result<Value, TransportError> {
+        let id = Uuid::new_v4().to_string();
+        let deadline = self.shared.timeouts.deadline_for(method, params.as_ref());
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+            id: JsonRpcId::String(id.clone()),
+        };
+
+        let rx = self.shared.pending.register(id.clone());
+
+        // The response can arrive in the POST body, on an SSE stream the server
+        // keeps open after answering, or on the GET stream, so read it in the background
+        let shared = self.shared.clone();
+        let request_id = id.clone();
+        tokio::spawn(async move {
+            let outcome = match shared.post(&JsonRpcMessage::Request(request)).await {
+                Ok(response) => shared.consume_body(response).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = outcome {
+                shared.pending.fail(&request_id, e);
+            }
+        });
+
+        await_response(self, &self.shared.pending, method, &id, rx, options, deadline).await
+    }
+
+    async fn send_notification(&self, method: &str, params: Option<Value>) -> Result<(), TransportError> {
+        let notification = JsonRpcNotification {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+        };
+
+        let response = self.shared.post(&JsonRpcMessage::Notification(notification)).await?;
+        self.shared.consume_body(response).await?;
+        self.ensure_listener();
+        Ok(())
+    }
+
+    fn set_protocol_version(&self, version: ProtocolVersion) {
+        if version.requires_version_header() {
+            *self.shared.protocol_version.write().unwrap() = Some(version);
+        }
+    }
+
+    async fn close(&self) -> Result<Option<ShutdownStage>, TransportError> {
+        if let Some(listener) = self.listener.lock().unwrap().take() {
+            listener.abort();
+        }
+
+        self.shared.pending.clear();
+
+        // Explicitly terminate the session; servers may answer 405 if they don't allow it
+        let session_id = self.shared.session_id.write().unwrap().take();
+        if let Some(session_id) = session_id {
+            match self.shared.client.delete(&self.shared.url)
+                .headers(self.shared.headers.clone())
+                .header(SESSION_ID_HEADER, session_id)
+                .send()
+                .await
+            {
+                Ok(response) => debug!("Session terminated with status {}", response.status()),
+                Err(e) => warn!("Failed to terminate session: {}", e),
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::http::{HeaderMap as AxumHeaders, StatusCode as AxumStatus};
+    use axum::response::sse::{Event, Sse};
+    use axum::response::{IntoResponse, Response};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::json;
+    use std::convert::Infallible;
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::UnboundedReceiverStream;
+
+    /// In-process stand-in for a Streamable HTTP server. `initialize` starts a
+    /// new session; other messages need the current session ID, or get 404.
+    #[derive(Default)]
+    struct FakeServer {
+        sessions: Mutex<u32>,
+        session: Mutex<Option<String>>,
+        /// Session header of every POST after `initialize`.
+        seen_sessions: Mutex<Vec<Option<String>>>,
+        deleted: Mutex<Vec<String>>,
+        get_stream: Mutex<Option<mpsc::UnboundedSender<Result<Event, Infallible>>>>,
+    }
+
+    fn session_header(headers: &AxumHeaders) -> Option<String> {
+        headers.get(SESSION_ID_HEADER).and_then(|v| v.to_str().ok()).map(str::to_string)
+    }
+
+    async fn handle_post(State(server): State<Arc<FakeServer>>, headers: AxumHeaders, body: String) -> Response {
+        let message: Value = serde_json::from_str(&body).unwrap();
+        let method = message["method"].as_str().unwrap_or_default().to_string();
+        let id = message["id"].clone();
+
+        if method == "initialize" {
+            let session_id = {
+                let mut sessions = server.sessions.lock().unwrap();
+                *sessions += 1;
+                format!("session-{}", sessions)
+            };
+            *server.session.lock().unwrap() = Some(session_id.clone());
+            let result = json!({ "jsonrpc": "2.0", "id": id, "result": { "protocolVersion": "2025-06-18" } });
+            return ([(SESSION_ID_HEADER, session_id)], Json(result)).into_response();
+        }
+
+        let session_id = session_header(&headers);
+        server.seen_sessions.lock().unwrap().push(session_id.clone());
+        if session_id.is_none() || session_id != *server.session.lock().unwrap() {
+            return AxumStatus::NOT_FOUND.into_response();
+        }
+        if id.is_null() {
+            return AxumStatus::ACCEPTED.into_response();
+        }
+
+        let response = json!({ "jsonrpc": "2.0", "id": id, "result": { "echo": message["params"] } });
+        match method.as_str() {
+            "sse/echo" => {
+                let progress = json!({ "jsonrpc": "2.0", "method": "notifications/progress", "params": {} });
+                let body = format!("data: {}\n\ndata: {}\n\n", progress, response);
+                ([(CONTENT_TYPE, "text/event-stream")], body).into_response()
+            }
+            _ => Json(response).into_response(),
+        }
+    }
+
+    async fn handle_get(State(server): State<Arc<FakeServer>>, headers: AxumHeaders) -> Response {
+        if session_header(&headers) != *server.session.lock().unwrap() {
+            return AxumStatus::NOT_FOUND.into_response();
+        }
+        let (events, stream) = mpsc::unbounded_channel();
+        *server.get_stream.lock().unwrap() = Some(events);
+        Sse::new(UnboundedReceiverStream::new(stream)).into_response()
+    }
+
+    async fn handle_delete(State(server): State<Arc<FakeServer>>, headers: AxumHeaders) -> AxumStatus {
+        server.deleted.lock().unwrap().extend(session_header(&headers));
+        AxumStatus::OK
+    }
+
+    async fn start(server: Arc<FakeServer>) -> (StreamableHttpTransport, mpsc::UnboundedReceiver<InboundMessage>) {
+        let app = Router::new()
+            .route("/mcp", post(handle_post).get(handle_get).delete(handle_delete))
+            .with_state(server);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let (inbound, inbound_rx) = mpsc::unbounded_channel();
+        let transport = StreamableHttpTransport::new(url, HashMap::new(), TimeoutConfig::default(), inbound).unwrap();
+        (transport, inbound_rx)
+    }
+
+    async fn initialize(transport: &StreamableHttpTransport) {
+        transport.send_request("initialize", Some(json!({}))).await.unwrap();
+        transport.send_notification("notifications/initialized", None).await.unwrap();
+    }
+
+    async fn next_inbound(inbound: &mut mpsc::UnboundedReceiver<InboundMessage>) -> InboundMessage {
+        tokio::time::timeout(Duration::from_secs(5), inbound.recv()).await
+            .expect("no inbound message")
+            .expect("inbound channel closed")
+    }
+
+    #[tokio::test]
+    async fn json_response() {
+        let (transport, _inbound) = start(Arc::default()).await;
+        initialize(&transport).await;
+
+        let result = transport.send_request("json/echo", Some(json!({ "n": 1 }))).await.unwrap();
+        assert_eq!(result, json!({ "echo": { "n": 1 } }));
+    }
+
+    #[tokio::test]
+    async fn sse_response() {
+        let (transport, mut inbound) = start(Arc::default()).await;
+        initialize(&transport).await;
+
+        let result = transport.send_request("sse/echo", Some(json!({ "n": 2 }))).await.unwrap();
+        assert_eq!(result, json!({ "echo": { "n": 2 } }));
+        match next_inbound(&mut inbound).await {
+            InboundMessage::Notification(notification) => assert_eq!(notification.method, "notifications/progress"),
+            _ => panic!("expected the notification sent before the response"),
+        }
+    }
+
+    #[tokio::test]
+    async fn session_id_round_trips() {
+        let server = Arc::new(FakeServer::default());
+        let (transport, _inbound) = start(server.clone()).await;
+        initialize(&transport).await;
+        transport.send_request("json/echo", None).await.unwrap();
+        transport.close().await.unwrap();
+
+        let session = Some("session-1".to_string());
+        assert_eq!(*server.seen_sessions.lock().unwrap(), vec![session.clone(), session]);
+        assert_eq!(*server.deleted.lock().unwrap(), vec!["session-1".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn get_stream_delivers_server_messages() {
+        let server = Arc::new(FakeServer::default());
+        let (transport, mut inbound) = start(server.clone()).await;
+        initialize(&transport).await;
+
+        let stream = loop {
+            if let Some(stream) = server.get_stream.lock().unwrap().clone() {
+                break stream;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/tools/list_changed" });
+        stream.send(Ok(Event::default().data(notification.to_string()))).unwrap();
+
+        match next_inbound(&mut inbound).await {
+            InboundMessage::Notification(notification) => assert_eq!(notification.method, "notifications/tools/list_changed"),
+            _ => panic!("expected a notification from the GET stream"),
+        }
+        transport.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn expired_session_asks_for_a_new_one() {
+        let server = Arc::new(FakeServer::default());
+        let (transport, mut inbound) = start(server.clone()).await;
+        initialize(&transport).await;
+
+        *server.session.lock().unwrap() = None;
+        assert!(transport.send_request("json/echo", None).await.is_err());
+        loop {
+            match next_inbound(&mut inbound).await {
+                InboundMessage::SessionLost(_) => break,
+                _ => continue,
+            }
+        }
+
+        // Initializing again starts a fresh session that later requests use
+        initialize(&transport).await;
+        transport.send_request("json/echo", None).await.unwrap();
+        assert_eq!(server.seen_sessions.lock().unwrap().last().unwrap().as_deref(), Some("session-2"));
+    }
+}
@@ -1,14 +1,23 @@
 use async_trait::async_trait;
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::process::Stdio;
+use std::process::{ExitStatus, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
 use tokio::process::{Child, Command};
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{mpsc, oneshot, watch, Notify};
+use tokio::task::AbortHandle;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use crate::domain::json_rpc::{JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, JsonRpcId};
+use crate::domain::json_rpc::{JsonRpcError, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, JsonRpcId};
+use crate::domain::notifications::CancelledParams;
+use crate::domain::protocol_version::ProtocolVersion;
+use crate::infrastructure::http_transport::StreamableHttpTransport;
+use crate::infrastructure::sse_transport::SseTransport;
+use crate::infrastructure::stderr_log::StderrLog;
 
 #[derive(Debug, thiserror::Error)]
 pub enum TransportError {
@@ -18,35 +27,456 @@ pub enum TransportError {
     Json(#[from] serde_json::Error),
     #[error("Channel closed")]
     ChannelClosed,
-    #[error("Request timeout")]
-    Timeout,
+    #[error("{method} request timed out after {}ms", .timeout.as_millis())]
+    Timeout {
+        method: String,
+        timeout: Duration,
+    },
+    #[error("{method} request exceeded the maximum duration of {}ms", .limit.as_millis())]
+    MaxDurationExceeded {
+        method: String,
+        limit: Duration,
+    },
+    #[error("Request cancelled: {0}")]
+    Cancelled(String),
     #[error("Process error: {0}")]
     Process(String),
+    #[error("Server process exited ({0})")]
+    ProcessExited(String),
+    #[error("HTTP error: {0}")]
+    Http(String),
+}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(error: reqwest::Error) -> Self {
+        TransportError::Http(error.to_string())
+    }
 }
 
 #[async_trait]
 pub trait Transport: Send + Sync {
-    async fn send_request(&self, method: &str, params: Option<Value>) -> Result<Value, TransportError>;
+    async fn send_request(&self, method: &str, params: Option<Value>) -> Result<Value, TransportError> {
+        self.send_request_with(method, params, &RequestOptions::default()).await
+    }
+    async fn send_request_with(&self, method: &str, params: Option<Value>, options: &RequestOptions) -> Result<Value, TransportError>;
     async fn send_notification(&self, method: &str, params: Option<Value>) -> Result<(), TransportError>;
-    async fn close(&mut self) -> Result<(), TransportError>;
+    /// Called once initialization has settled on a protocol version, for
+    /// transports whose framing depends on it.
+    fn set_protocol_version(&self, _version: ProtocolVersion) {}
+    /// Ends the connection. Transports that run a server process report how
+    /// it was stopped.
+    async fn close(&self) -> Result<Option<ShutdownStage>, TransportError>;
+    /// Resolves with the reason once the connection ends on its own, such as
+    /// the server process exiting. Transports that recover by themselves
+    /// never resolve.
+    async fn closed(&self) -> String {
+        std::future::pending().await
+    }
+}
+
+/// Per-request settings passed to `Transport::send_request_with`.
+#[derive(Clone, Default)]
+pub struct RequestOptions {
+    /// Abandons the request when cancelled.
+    pub cancellation: Option<CancellationToken>,
+    /// Each notification restarts the request timeout, for requests that
+    /// report progress while they run.
+    pub keepalive: Option<Arc<Notify>>,
+}
+
+/// Cancels an in-flight request from another task. Clones share the same
+/// state; only the first reason given is kept.
+#[derive(Clone)]
+pub struct CancellationToken {
+    reason: Arc<watch::Sender<Option<String>>>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        let (reason, _) = watch::channel(None);
+        Self { reason: Arc::new(reason) }
+    }
+
+    pub fn cancel(&self, reason: impl Into<String>) {
+        let reason = reason.into();
+        self.reason.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+            *current = Some(reason);
+            true
+        });
+    }
+
+    /// Resolves with the reason once the token is cancelled.
+    pub async fn cancelled(&self) -> String {
+        let mut rx = self.reason.subscribe();
+        let reason = rx.wait_for(Option::is_some).await
+            .map(|reason| reason.clone().unwrap_or_default());
+        // The sender lives in `self`, so the channel can't close while we wait
+        reason.unwrap_or_default()
+    }
+}
+
+/// Waits for the response to request `id`. On timeout or cancellation the
+/// pending entry is dropped and the server is sent `notifications/cancelled`
+/// so it can stop working on the request.
+pub(crate) async fn await_response<T: Transport + ?Sized>(
+    transport: &T,
+    pending: &PendingRequests,
+    method: &str,
+    id: &str,
+    response: oneshot::Receiver<Result<Value, TransportError>>,
+    options: &RequestOptions,
+    deadline: RequestDeadline,
+) -> Result<Value, TransportError> {
+    let cancelled = async {
+        match options.cancellation {
+            Some(ref token) => token.cancelled().await,
+            None => std::future::pending().await,
+        }
+    };
+    let max_total = async {
+        match deadline.max_total {
+            Some(limit) => tokio::time::sleep(limit).await,
+            None => std::future::pending().await,
+        }
+    };
+    let timeout = tokio::time::sleep(deadline.timeout);
+    tokio::pin!(response, cancelled, max_total, timeout);
+
+    let error = loop {
+        tokio::select! {
+            result = &mut response => return result.unwrap_or(Err(TransportError::ChannelClosed)),
+            reason = &mut cancelled => break TransportError::Cancelled(reason),
+            _ = &mut timeout => break TransportError::Timeout {
+                method: method.to_string(),
+                timeout: deadline.timeout,
+            },
+            _ = &mut max_total => break TransportError::MaxDurationExceeded {
+                method: method.to_string(),
+                limit: deadline.max_total.unwrap_or_default(),
+            },
+            _ = keepalive(options) => {
+                debug!("Progress on {} request {}, restarting timeout", method, id);
+                timeout.as_mut().reset(tokio::time::Instant::now() + deadline.timeout);
+            }
+        }
+    };
+    let reason = match error {
+        TransportError::Cancelled(ref reason) => reason.clone(),
+        ref other => other.to_string(),
+    };
+
+    pending.remove(id);
+    info!("Abandoning {} request {}: {}", method, id, reason);
+
+    // The spec doesn't allow cancelling initialize
+    if method != "initialize" {
+        let params = CancelledParams {
+            request_id: JsonRpcId::String(id.to_string()),
+            reason: Some(reason),
+        };
+        if let Err(e) = transport.send_notification("notifications/cancelled", Some(json!(params))).await {
+            warn!("Failed to notify server of cancelled request {}: {}", id, e);
+        }
+    }
+    Err(error)
+}
+
+async fn keepalive(options: &RequestOptions) {
+    match options.keepalive {
+        Some(ref notify) => notify.notified().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Something the client has to act on that isn't a reply to one of its
+/// requests: a message initiated by the server, or news that the server lost
+/// the session.
+pub enum InboundMessage {
+    /// A request the client must answer by sending a response through
+    /// `responder`, or `None` if the server cancelled it, as cancelled
+    /// requests must not be answered.
+    Request {
+        request: JsonRpcRequest,
+        responder: oneshot::Sender<Option<JsonRpcResponse>>,
+    },
+    Notification(JsonRpcNotification),
+    /// The server no longer knows the session, for example because it expired.
+    /// The connection is still usable but must be initialized again.
+    SessionLost(String),
+}
+
+/// Channel through which transports hand server-initiated messages to the client.
+pub type InboundSender = mpsc::UnboundedSender<InboundMessage>;
+
+/// Passes a server request to the client and waits for the response to send
+/// back, if any.
+pub(crate) async fn route_request(inbound: InboundSender, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+    let id = request.id.clone();
+    let (responder, reply) = oneshot::channel();
+
+    if inbound.send(InboundMessage::Request { request, responder }).is_err() {
+        return Some(JsonRpcResponse::error(id, JsonRpcError::internal_error("Client is not accepting requests")));
+    }
+    reply.await.unwrap_or_else(|_| {
+        Some(JsonRpcResponse::error(id, JsonRpcError::internal_error("Request handler dropped without responding")))
+    })
 }
 
+/// How long to wait for a server's remaining stderr output once it has exited.
+const STDERR_DRAIN_TIMEOUT: Duration = Duration::from_millis(200);
+
 pub struct StdioTransport {
-    child: Option<Child>,
+    shutdown: Arc<Notify>,
+    exit: watch::Receiver<Option<ProcessExit>>,
     stdin_tx: mpsc::UnboundedSender<JsonRpcMessage>,
-    pending_requests: mpsc::UnboundedSender<(String, oneshot::Sender<Result<Value, TransportError>>)>,
+    pending: Arc<PendingRequests>,
+    timeouts: TimeoutConfig,
     _handles: Vec<tokio::task::JoinHandle<()>>,
 }
 
+#[derive(Debug, Clone)]
 pub struct ServerConfig {
     pub command: String,
     pub args: Vec<String>,
     pub cwd: Option<String>,
     pub env: Option<HashMap<String, String>>,
+    pub transport: TransportKind,
+    pub timeouts: TimeoutConfig,
+    pub restart: RestartPolicy,
+    pub shutdown: ShutdownConfig,
+}
+
+/// Grace periods for stopping a stdio server: after its stdin is closed,
+/// then after SIGTERM, before it is killed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShutdownConfig {
+    pub stdin_grace_ms: u64,
+    pub term_grace_ms: u64,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            stdin_grace_ms: 2_000,
+            term_grace_ms: 3_000,
+        }
+    }
+}
+
+/// The step of the shutdown sequence that stopped a stdio server.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownStage {
+    /// It had exited before the shutdown began.
+    AlreadyExited,
+    /// It exited on its own once stdin was closed.
+    StdinClosed,
+    /// It exited after SIGTERM.
+    Terminated,
+    /// It had to be killed.
+    Killed,
+}
+
+impl std::fmt::Display for ShutdownStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ShutdownStage::AlreadyExited => "had already exited",
+            ShutdownStage::StdinClosed => "exited after stdin was closed",
+            ShutdownStage::Terminated => "exited after SIGTERM",
+            ShutdownStage::Killed => "was killed",
+        })
+    }
+}
+
+/// How a stdio server process ended.
+#[derive(Debug, Clone)]
+struct ProcessExit {
+    reason: String,
+    /// Set when the process was stopped by `close`.
+    stage: Option<ShutdownStage>,
+}
+
+/// Whether and how to restart a server whose process exits on its own.
+/// Only applies to stdio servers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestartPolicy {
+    pub enabled: bool,
+    /// Restarts allowed in a row before giving up. The count starts over once
+    /// a restarted server has stayed up for a minute.
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_attempts: 5,
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Delay before restart number `attempt`, counting from zero.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff_ms = self.initial_backoff_ms.saturating_mul(1 << attempt.min(16));
+        Duration::from_millis(backoff_ms.min(self.max_backoff_ms))
+    }
+}
+
+/// Request timeouts for one server, in milliseconds. The most specific
+/// setting wins: per tool (for `tools/call`), then per method, then the default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    pub default_ms: u64,
+    pub methods: HashMap<String, u64>,
+    pub tools: HashMap<String, u64>,
+    /// Hard limit on a request's total duration, even while progress keeps
+    /// restarting its timeout.
+    pub max_total_ms: Option<u64>,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            default_ms: 30_000,
+            methods: HashMap::new(),
+            tools: HashMap::new(),
+            max_total_ms: None,
+        }
+    }
+}
+
+impl TimeoutConfig {
+    pub(crate) fn deadline_for(&self, method: &str, params: Option<&Value>) -> RequestDeadline {
+        let tool = (method == "tools/call")
+            .then(|| params.and_then(|p| p.get("name")).and_then(Value::as_str))
+            .flatten();
+
+        let timeout_ms = tool.and_then(|name| self.tools.get(name))
+            .or_else(|| self.methods.get(method))
+            .copied()
+            .unwrap_or(self.default_ms);
+
+        RequestDeadline {
+            timeout: Duration::from_millis(timeout_ms),
+            max_total: self.max_total_ms.map(Duration::from_millis),
+        }
+    }
+}
+
+/// The timeouts that apply to one request.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequestDeadline {
+    timeout: Duration,
+    max_total: Option<Duration>,
+}
+
+/// Which transport to use to reach the server. `Stdio` spawns `command`;
+/// the HTTP variants ignore the process fields and connect to `url`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransportKind {
+    #[default]
+    Stdio,
+    StreamableHttp {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    /// Legacy HTTP+SSE transport from protocol revision 2024-11-05.
+    Sse {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
+/// Creates the transport selected by `config.transport`. A stdio server's
+/// stderr goes to `stderr`.
+pub async fn connect_transport(
+    config: ServerConfig,
+    inbound: InboundSender,
+    stderr: Arc<StderrLog>,
+) -> Result<Box<dyn Transport>, TransportError> {
+    match config.transport.clone() {
+        TransportKind::Stdio => Ok(Box::new(StdioTransport::new(config, inbound, stderr).await?)),
+        TransportKind::StreamableHttp { url, headers } => {
+            Ok(Box::new(StreamableHttpTransport::new(url, headers, config.timeouts, inbound)?))
+        }
+        TransportKind::Sse { url, headers } => {
+            Ok(Box::new(SseTransport::new(url, headers, config.timeouts, inbound).await?))
+        }
+    }
+}
+
+/// Requests awaiting a response, keyed by JSON-RPC request ID.
+#[derive(Default)]
+pub(crate) struct PendingRequests {
+    senders: std::sync::Mutex<HashMap<String, oneshot::Sender<Result<Value, TransportError>>>>,
+}
+
+impl PendingRequests {
+    pub fn register(&self, id: String) -> oneshot::Receiver<Result<Value, TransportError>> {
+        let (tx, rx) = oneshot::channel();
+        self.senders.lock().unwrap().insert(id, tx);
+        rx
+    }
+
+    /// Resolves the request the response belongs to, if it is still pending.
+    pub fn complete(&self, response: JsonRpcResponse) {
+        if let JsonRpcId::String(ref id) = response.id {
+            if let Some(sender) = self.senders.lock().unwrap().remove(id) {
+                let _ = sender.send(response_to_result(response));
+            }
+        }
+    }
+
+    pub fn fail(&self, id: &str, error: TransportError) {
+        if let Some(sender) = self.senders.lock().unwrap().remove(id) {
+            let _ = sender.send(Err(error));
+        }
+    }
+
+    pub fn remove(&self, id: &str) {
+        self.senders.lock().unwrap().remove(id);
+    }
+
+    /// Drops every pending request; their callers see `ChannelClosed`.
+    pub fn clear(&self) {
+        self.senders.lock().unwrap().clear();
+    }
+
+    /// Fails every pending request with the error `error` builds.
+    pub fn fail_all(&self, error: impl Fn() -> TransportError) {
+        for (_, sender) in self.senders.lock().unwrap().drain() {
+            let _ = sender.send(Err(error()));
+        }
+    }
+}
+
+/// Converts a JSON-RPC response into the result handed back to the caller.
+pub(crate) fn response_to_result(response: JsonRpcResponse) -> Result<Value, TransportError> {
+    if let Some(result) = response.result {
+        Ok(result)
+    } else if let Some(error) = response.error {
+        Err(TransportError::Process(format!("RPC error {}: {}", error.code, error.message)))
+    } else {
+        Err(TransportError::Process("Invalid response".into()))
+    }
 }
 
 impl StdioTransport {
-    pub async fn new(config: ServerConfig) -> Result<Self, TransportError> {
+    pub async fn new(config: ServerConfig, inbound: InboundSender, stderr_log: Arc<StderrLog>) -> Result<Self, TransportError> {
         info!("Starting MCP server: {} {:?}", config.command, config.args);
         
         let mut cmd = Command::new(&config.command);
@@ -55,6 +485,9 @@ impl StdioTransport {
            .stdout(Stdio::piped())
            .stderr(Stdio::piped())
            .kill_on_drop(true);
+        // Lead a new process group so shutdown can signal the server's own children too
+        #[cfg(unix)]
+        cmd.process_group(0);
         
         if let Some(ref cwd) = config.cwd {
             cmd.current_dir(cwd);
@@ -94,38 +527,7 @@ impl StdioTransport {
         })?;
         
         let (stdin_tx, mut stdin_rx) = mpsc::unbounded_channel::<JsonRpcMessage>();
-        let (pending_tx, mut pending_rx) = mpsc::unbounded_channel::<(String, oneshot::Sender<Result<Value, TransportError>>)>();
-        let (response_tx, mut response_rx) = mpsc::unbounded_channel::<JsonRpcResponse>();
-        
-        let mut pending_requests = HashMap::<String, oneshot::Sender<Result<Value, TransportError>>>::new();
-        
-        // Handle pending requests and responses
-        let pending_handle = tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    // New pending request
-                    Some((id, sender)) = pending_rx.recv() => {
-                        pending_requests.insert(id, sender);
-                    }
-                    // Response received
-                    Some(response) = response_rx.recv() => {
-                        if let JsonRpcId::String(id) = response.id {
-                            if let Some(sender) = pending_requests.remove(&id) {
-                                let result = if let Some(result) = response.result {
-                                    Ok(result)
-                                } else if let Some(error) = response.error {
-                                    Err(TransportError::Process(format!("RPC error {}: {}", error.code, error.message)))
-                                } else {
-                                    Err(TransportError::Process("Invalid response".into()))
-                                };
-                                let _ = sender.send(result);
-                            }
-                        }
-                    }
-                    else => break,
-                }
-            }
-        });
+        let pending = Arc::new(PendingRequests::default());
         
         // Stdin writer
         let stdin_handle = tokio::spawn(async move {
@@ -156,6 +558,8 @@ impl StdioTransport {
         });
         
         // Stdout reader
+        let reply_tx = stdin_tx.clone();
+        let responses = pending.clone();
         let stdout_handle = tokio::spawn(async move {
             let reader = BufReader::new(stdout);
             let mut lines = reader.lines();
@@ -170,16 +574,23 @@ impl StdioTransport {
                 match serde_json::from_str::<JsonRpcMessage>(&line) {
                     Ok(JsonRpcMessage::Response(response)) => {
                         info!("Received response for request ID: {:?}", response.id);
-                        if response_tx.send(response).is_err() {
-                            error!("Failed to send response to handler");
-                            break;
-                        }
+                        responses.complete(response);
                     }
                     Ok(JsonRpcMessage::Notification(notification)) => {
-                        info!("Received notification: {} with params: {:?}", notification.method, notification.params);
+                        debug!("Received notification: {}", notification.method);
+                        if inbound.send(InboundMessage::Notification(notification)).is_err() {
+                            warn!("Dropping notification, client is no longer listening");
+                        }
                     }
                     Ok(JsonRpcMessage::Request(request)) => {
-                        warn!("Received unexpected request from server: {} with ID: {:?}", request.method, request.id);
+                        info!("Received request from server: {} with ID: {:?}", request.method, request.id);
+                        let inbound = inbound.clone();
+                        let reply_tx = reply_tx.clone();
+                        tokio::spawn(async move {
+                            if let Some(response) = route_request(inbound, request).await {
+                                let _ = reply_tx.send(JsonRpcMessage::Response(response));
+                            }
+                        });
                     }
                     Err(e) => {
                         error!("Failed to parse JSON-RPC message: {} - Raw line: {}", e, line);
@@ -196,23 +607,139 @@ impl StdioTransport {
             while let Ok(Some(line)) = lines.next_line().await {
                 if !line.trim().is_empty() {
                     info!("Server stderr: {}", line);
+                    stderr_log.push(line);
                 }
             }
         });
         
+        // Exit monitor, which also carries out `close`
+        let shutdown = Arc::new(Notify::new());
+        let (exit_tx, exit) = watch::channel(None);
+        let exit_handle = tokio::spawn({
+            let shutdown = shutdown.clone();
+            let stdin = stdin_handle.abort_handle();
+            let grace = config.shutdown.clone();
+            let pending = pending.clone();
+            let stderr_drained = stderr_handle;
+            async move {
+                let (status, stage) = tokio::select! {
+                    status = child.wait() => (status, None),
+                    _ = shutdown.notified() => {
+                        let (status, stage) = shut_down(&mut child, stdin, &grace).await;
+                        (status, Some(stage))
+                    }
+                };
+                let reason = match status {
+                    Ok(status) => status.to_string(),
+                    Err(e) => format!("failed to wait for process: {}", e),
+                };
+                match stage {
+                    Some(stage) => info!("MCP server process {}: {}", stage, reason),
+                    None => info!("MCP server process exited: {}", reason),
+                }
+                // Let the last words reach the stderr log before anyone reports the exit
+                let _ = tokio::time::timeout(STDERR_DRAIN_TIMEOUT, stderr_drained).await;
+                
+                // Publish the exit before failing requests, so none can slip in between
+                exit_tx.send_replace(Some(ProcessExit { reason: reason.clone(), stage }));
+                pending.fail_all(|| TransportError::ProcessExited(reason.clone()));
+            }
+        });
+        
         Ok(Self {
-            child: Some(child),
+            shutdown,
+            exit,
             stdin_tx,
-            pending_requests: pending_tx,
-            _handles: vec![pending_handle, stdin_handle, stdout_handle, stderr_handle],
+            pending,
+            timeouts: config.timeouts,
+            _handles: vec![stdin_handle, stdout_handle, exit_handle],
         })
     }
 }
 
+impl StdioTransport {
+    async fn exited(&self) -> ProcessExit {
+        let mut exit = self.exit.clone();
+        let result = exit.wait_for(Option::is_some).await
+            .map(|exit| exit.clone());
+        // The monitor only drops the sender after publishing the exit
+        match result {
+            Ok(Some(exit)) => exit,
+            _ => ProcessExit { reason: "exit monitor stopped".into(), stage: None },
+        }
+    }
+}
+
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        // The exit monitor owns the child, so it has to be told to stop it
+        self.shutdown.notify_one();
+    }
+}
+
+/// Stops a server the way the MCP spec recommends: close its stdin and wait,
+/// then send SIGTERM and wait, then kill it. Signals go to the server's whole
+/// process group, so children started by launchers like `npx` or `uvx` don't
+/// outlive it. Elsewhere than Unix the server is killed without SIGTERM.
+async fn shut_down(
+    child: &mut Child,
+    stdin: AbortHandle,
+    grace: &ShutdownConfig,
+) -> (std::io::Result<ExitStatus>, ShutdownStage) {
+    let pid = child.id();
+    // Dropping the writer closes the pipe
+    stdin.abort();
+    
+    let stdin_grace = Duration::from_millis(grace.stdin_grace_ms);
+    if let Ok(status) = tokio::time::timeout(stdin_grace, child.wait()).await {
+        kill_process_group(pid);
+        return (status, ShutdownStage::StdinClosed);
+    }
+    
+    #[cfg(unix)]
+    if let Some(pid) = pid {
+        info!("MCP server did not exit after stdin was closed, sending SIGTERM");
+        signal_process_group(pid, libc::SIGTERM);
+        let term_grace = Duration::from_millis(grace.term_grace_ms);
+        if let Ok(status) = tokio::time::timeout(term_grace, child.wait()).await {
+            kill_process_group(Some(pid));
+            return (status, ShutdownStage::Terminated);
+        }
+    }
+    
+    warn!("MCP server did not exit in time, killing it");
+    kill_process_group(pid);
+    let _ = child.start_kill();
+    (child.wait().await, ShutdownStage::Killed)
+}
+
+/// Kills whatever is left of the server's process group once the server is gone.
+fn kill_process_group(pid: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(pid) = pid {
+        signal_process_group(pid, libc::SIGKILL);
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+#[cfg(unix)]
+fn signal_process_group(pid: u32, signal: libc::c_int) {
+    // The server leads its own group, so its pid is the group id
+    let Ok(pgid) = libc::pid_t::try_from(pid) else {
+        return;
+    };
+    // SAFETY: kill only sends a signal; an already empty group just yields ESRCH
+    unsafe {
+        libc::kill(-pgid, signal);
+    }
+}
+
 #[async_trait]
 impl Transport for StdioTransport {
-    async fn send_request(&self, method: &str, params: Option<Value>) -> Result<Value, TransportError> {
+    async fn send_request_with(&self, method: &str, params: Option<Value>, options: &RequestOptions) -> Result<Value, TransportError> {
         let id = Uuid::new_v4().to_string();
+        let deadline = self.timeouts.deadline_for(method, params.as_ref());
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
             method: method.to_string(),
@@ -220,22 +747,20 @@ impl Transport for StdioTransport {
             id: JsonRpcId::String(id.clone()),
         };
         
-        let (tx, rx) = oneshot::channel();
-        
         // Register pending request
-        self.pending_requests.send((id.clone(), tx))
-            .map_err(|_| TransportError::ChannelClosed)?;
+        let rx = self.pending.register(id.clone());
+        if let Some(exit) = self.exit.borrow().clone() {
+            self.pending.remove(&id);
+            return Err(TransportError::ProcessExited(exit.reason));
+        }
         
         // Send request
-        self.stdin_tx.send(JsonRpcMessage::Request(request))
-            .map_err(|_| TransportError::ChannelClosed)?;
-        
-        // Wait for response with timeout
-        match tokio::time::timeout(std::time::Duration::from_secs(30), rx).await {
-            Ok(Ok(result)) => result,
-            Ok(Err(_)) => Err(TransportError::ChannelClosed),
-            Err(_) => Err(TransportError::Timeout),
+        if self.stdin_tx.send(JsonRpcMessage::Request(request)).is_err() {
+            self.pending.remove(&id);
+            return Err(TransportError::ChannelClosed);
         }
+        
+        await_response(self, &self.pending, method, &id, rx, options, deadline).await
     }
     
     async fn send_notification(&self, method: &str, params: Option<Value>) -> Result<(), TransportError> {
@@ -251,11 +776,13 @@ impl Transport for StdioTransport {
         Ok(())
     }
     
-    async fn close(&mut self) -> Result<(), TransportError> {
-        if let Some(mut child) = self.child.take() {
-            let _ = child.kill().await;
-            let _ = child.wait().await;
-        }
-        Ok(())
+    async fn close(&self) -> Result<Option<ShutdownStage>, TransportError> {
+        self.shutdown.notify_one();
+        let exit = self.exited().await;
+        Ok(Some(exit.stage.unwrap_or(ShutdownStage::AlreadyExited)))
+    }
+    
+    async fn closed(&self) -> String {
+        self.exited().await.reason
     }
 }
\ No newline at end of file
@@ -1,4 +1,12 @@
-pub mod mcp_client;
-pub mod stdio_transport;
 pub mod mcp_transport;
+pub mod http_transport;
+pub mod sse_transport;
+pub mod sse;
+pub mod request_handlers;
+pub mod events;
+pub mod stderr_log;
+pub mod server_log;
+pub mod roots;
+pub mod sampling;
+pub mod elicitation;
 pub mod proper_mcp_client;
\ No newline at end of file
@@ -1,23 +1,100 @@
+use crate::domain::json_rpc::{JsonRpcError, JsonRpcResponse};
+use crate::domain::json_schema::{self, SchemaViolation, ValidationError};
 use crate::domain::mcp_types::*;
-use crate::infrastructure::mcp_transport::{ServerConfig, StdioTransport, Transport, TransportError};
+use crate::domain::notifications::ServerNotification;
+use crate::domain::protocol_version::ProtocolVersion;
+use crate::infrastructure::events::{ClientEvent, EventBus, ResourceUpdate, ToolProgress};
+use crate::infrastructure::mcp_transport::{
+    connect_transport, CancellationToken, InboundMessage, InboundSender, RequestOptions, ServerConfig,
+    ShutdownStage, Transport, TransportError,
+};
+use crate::infrastructure::request_handlers::RequestHandlerRegistry;
+use crate::infrastructure::server_log::{LogEntry, LogFilter, ServerLog};
+use crate::infrastructure::stderr_log::{StderrLine, StderrLog};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{debug, error, info};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{mpsc, Notify, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+/// Per-call settings for `McpClientHandle::call_tool_with`.
+#[derive(Clone, Default)]
+pub struct ToolCallOptions {
+    pub cancellation: Option<CancellationToken>,
+    /// Sent as `_meta.progressToken`; the server's progress for the call is
+    /// published as `ClientEvent::ToolProgress`.
+    pub progress_token: Option<String>,
+    /// Restart the request timeout whenever progress arrives.
+    pub reset_timeout_on_progress: bool,
+}
+
+/// Upper bound on pages fetched for one list, in case a server never stops paginating.
+const MAX_LIST_PAGES: usize = 100;
+
+/// Stderr lines appended to connection errors.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// How long a restarted server must stay up before its restart count starts over.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+fn supported_versions() -> String {
+    ProtocolVersion::SUPPORTED.map(ProtocolVersion::as_str).join(", ")
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum McpClientError {
     #[error("Transport error: {0}")]
-    Transport(#[from] TransportError),
+    Transport(TransportError),
+    /// The request was abandoned at the caller's request, so it didn't fail.
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
     #[error("Protocol error: {0}")]
     Protocol(String),
     #[error("Not connected")]
     NotConnected,
+    #[error("Server speaks protocol version {server}, which this client does not support (supported: {})", supported_versions())]
+    UnsupportedProtocolVersion {
+        server: String,
+    },
+    #[error("Unknown tool: {0}")]
+    UnknownTool(String),
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(String),
+    #[error("Invalid arguments for tool '{tool}': {}", json_schema::describe(.violations))]
+    InvalidToolArguments {
+        tool: String,
+        violations: Vec<SchemaViolation>,
+    },
+    #[error("Tool '{tool}' returned structured content that does not match its output schema: {}", json_schema::describe(.violations))]
+    InvalidToolOutput {
+        tool: String,
+        violations: Vec<SchemaViolation>,
+    },
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
+    #[error("{error}\nServer stderr:\n{}", .stderr.join("\n"))]
+    WithStderr {
+        error: Box<McpClientError>,
+        stderr: Vec<String>,
+    },
+}
+
+impl From<TransportError> for McpClientError {
+    fn from(error: TransportError) -> Self {
+        match error {
+            TransportError::Cancelled(reason) => McpClientError::Cancelled(reason),
+            other => McpClientError::Transport(other),
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", content = "message", rename_all = "lowercase")]
 pub enum ClientState {
     Disconnected,
     Connecting,
@@ -25,26 +102,260 @@ pub enum ClientState {
     Error(String),
 }
 
+/// Owns the connection to one MCP server: connecting, initializing,
+/// background tasks and disconnecting. Requests go through `McpClientHandle`.
 pub struct ProperMcpClient {
-    transport: Option<Box<dyn Transport>>,
+    handle: McpClientHandle,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+/// Issues requests on a client's connection. Clones share the connection,
+/// caches and event bus and take no lock, so any number of requests can be
+/// in flight at once; the transport matches responses by request id.
+#[derive(Clone)]
+pub struct McpClientHandle {
+    transport: Arc<std::sync::RwLock<Option<Arc<dyn Transport>>>>,
     state: Arc<RwLock<ClientState>>,
     server_capabilities: Arc<RwLock<Option<ServerCapabilities>>>,
     tools: Arc<RwLock<Vec<Tool>>>,
+    prompts: Arc<RwLock<Vec<Prompt>>>,
+    resource_subscriptions: Arc<RwLock<HashSet<String>>>,
+    /// Settled during `initialize`; gates version-specific behavior.
+    protocol_version: Arc<std::sync::RwLock<Option<ProtocolVersion>>>,
+    request_handlers: RequestHandlerRegistry,
+    /// Set with `set_log_level`; applied again when the server restarts.
+    log_level: Arc<RwLock<Option<LoggingLevel>>>,
+    server_log: Arc<ServerLog>,
+    stderr: Arc<StderrLog>,
+    events: EventBus,
 }
 
 impl ProperMcpClient {
     pub fn new() -> Self {
+        let events = EventBus::new();
         Self {
-            transport: None,
-            state: Arc::new(RwLock::new(ClientState::Disconnected)),
-            server_capabilities: Arc::new(RwLock::new(None)),
-            tools: Arc::new(RwLock::new(Vec::new())),
+            handle: McpClientHandle {
+                transport: Arc::new(std::sync::RwLock::new(None)),
+                state: Arc::new(RwLock::new(ClientState::Disconnected)),
+                server_capabilities: Arc::new(RwLock::new(None)),
+                tools: Arc::new(RwLock::new(Vec::new())),
+                prompts: Arc::new(RwLock::new(Vec::new())),
+                resource_subscriptions: Arc::new(RwLock::new(HashSet::new())),
+                protocol_version: Arc::new(std::sync::RwLock::new(None)),
+                request_handlers: RequestHandlerRegistry::new(),
+                log_level: Arc::new(RwLock::new(None)),
+                server_log: Arc::new(ServerLog::new()),
+                stderr: Arc::new(StderrLog::new(events.clone())),
+                events,
+            },
+            tasks: Vec::new(),
+        }
+    }
+    
+    /// A handle for issuing requests. It stays valid across reconnects and
+    /// returns `NotConnected` while there is no connection.
+    pub fn handle(&self) -> McpClientHandle {
+        self.handle.clone()
+    }
+    
+    /// Subscribes to connection state changes and server notifications.
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<ClientEvent> {
+        self.handle.events.subscribe()
+    }
+    
+    /// The bus this client's events are published on, for request handlers
+    /// that need to involve the user.
+    pub fn events(&self) -> EventBus {
+        self.handle.events.clone()
+    }
+    
+    /// Handlers for requests the server sends to the client. Handlers can be
+    /// registered at any time; they apply to the current and future connections,
+    /// but the capabilities they imply are only advertised on the next connect.
+    pub fn request_handlers(&self) -> &RequestHandlerRegistry {
+        &self.handle.request_handlers
+    }
+    
+    /// Spawns the task that answers server requests and consumes server notifications.
+    fn spawn_dispatcher(&mut self, mut inbound_rx: mpsc::UnboundedReceiver<InboundMessage>) {
+        let handlers = self.handle.request_handlers.clone();
+        let events = self.handle.events.clone();
+        let protocol_version = self.handle.protocol_version.clone();
+        let server_log = self.handle.server_log.clone();
+        let handle = self.handle.clone();
+        self.tasks.push(tokio::spawn(async move {
+            while let Some(message) = inbound_rx.recv().await {
+                match message {
+                    InboundMessage::Request { request, responder } => {
+                        // Only pings may arrive before a version is agreed on
+                        let allowed = match *protocol_version.read().unwrap() {
+                            Some(version) => version.allows_server_request(&request.method),
+                            None => request.method == "ping",
+                        };
+                        if !allowed {
+                            warn!("Server sent {}, which the negotiated protocol version does not allow", request.method);
+                            let _ = responder.send(Some(JsonRpcResponse::error(
+                                request.id,
+                                JsonRpcError::method_not_found(&request.method),
+                            )));
+                            continue;
+                        }
+
+                        // Handlers may wait on the user, so don't block other messages
+                        let handlers = handlers.clone();
+                        tokio::spawn(async move {
+                            let response = handlers.dispatch(request).await;
+                            let _ = responder.send(response);
+                        });
+                    }
+                    InboundMessage::Notification(notification) => {
+                        let notification = ServerNotification::from(notification);
+                        debug!("Received notification: {}", notification.method());
+                        match notification {
+                            ServerNotification::LoggingMessage(ref message) => server_log.push(message.clone()),
+                            ServerNotification::Cancelled(ref cancelled) => {
+                                let reason = cancelled.reason.as_deref().unwrap_or("Cancelled by server");
+                                handlers.cancel(&cancelled.request_id, reason);
+                            }
+                            _ => {}
+                        }
+                        events.publish(ClientEvent::Notification(notification));
+                    }
+                    InboundMessage::SessionLost(reason) => {
+                        let handle = handle.clone();
+                        tokio::spawn(async move { handle.renew_session(&reason).await });
+                    }
+                }
+            }
+            debug!("Inbound message dispatcher ended");
+        }));
+    }
+    
+    /// Keeps the cached tool list current when the server announces changes.
+    async fn spawn_tool_refresher(&mut self) {
+        let announces_changes = self.handle.server_capabilities.read().await.as_ref()
+            .and_then(|caps| caps.tools.as_ref())
+            .and_then(|tools| tools.list_changed)
+            .unwrap_or(false);
+        if !announces_changes {
+            return;
+        }
+        
+        let handle = self.handle.clone();
+        let mut subscription = self.handle.events.subscribe();
+        self.tasks.push(tokio::spawn(async move {
+            loop {
+                match subscription.recv().await {
+                    Ok(ClientEvent::Notification(ServerNotification::ToolListChanged)) => {}
+                    // Missed events may have included a change
+                    Err(RecvError::Lagged(_)) => {}
+                    Ok(_) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+                
+                info!("Server tool list changed, refreshing tools");
+                match handle.fetch_tools().await {
+                    Ok(fetched) => handle.store_tools(fetched).await,
+                    Err(e) => error!("Failed to refresh tools: {}", e),
+                }
+            }
+        }));
+    }
+    
+    /// Re-reads subscribed resources when the server reports they changed.
+    async fn spawn_resource_watcher(&mut self) {
+        let supports_subscribe = self.handle.server_capabilities.read().await.as_ref()
+            .and_then(|caps| caps.resources.as_ref())
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false);
+        if !supports_subscribe {
+            return;
         }
+        
+        let handle = self.handle.clone();
+        let mut subscription = self.handle.events.subscribe();
+        self.tasks.push(tokio::spawn(async move {
+            loop {
+                let uri = match subscription.recv().await {
+                    Ok(ClientEvent::Notification(ServerNotification::ResourceUpdated(params))) => params.uri,
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                if !handle.resource_subscriptions.read().await.contains(&uri) {
+                    continue;
+                }
+                
+                info!("Subscribed resource updated: {}", uri);
+                match handle.read_resource(&uri).await {
+                    Ok(response) => handle.events.publish(ClientEvent::ResourceUpdated(ResourceUpdate {
+                        uri,
+                        contents: response.contents,
+                    })),
+                    Err(e) => error!("Failed to re-read resource {}: {}", uri, e),
+                }
+            }
+        }));
+    }
+    
+    /// Moves to the error state as soon as the connection ends on its own,
+    /// failing its pending requests, and restarts it if the server's restart
+    /// policy allows.
+    fn spawn_supervisor(&mut self, config: ServerConfig, inbound: InboundSender) {
+        let handle = self.handle.clone();
+        self.tasks.push(tokio::spawn(async move {
+            let policy = config.restart.clone();
+            let mut attempts = 0;
+            let mut connected_at = Instant::now();
+            
+            loop {
+                let Ok(transport) = handle.transport() else {
+                    break;
+                };
+                let reason = transport.closed().await;
+                drop(transport);
+                error!("Server connection lost: {}", reason);
+                handle.set_state(ClientState::Error(format!("Server process exited ({})", reason))).await;
+                if !policy.enabled {
+                    break;
+                }
+                if connected_at.elapsed() >= STABLE_UPTIME {
+                    attempts = 0;
+                }
+                
+                loop {
+                    if attempts >= policy.max_attempts {
+                        error!("Giving up on server after {} restart attempts", attempts);
+                        handle.set_state(ClientState::Error(format!(
+                            "Server process exited ({}); gave up after {} restart attempts", reason, attempts
+                        ))).await;
+                        return;
+                    }
+                    let backoff = policy.backoff(attempts);
+                    attempts += 1;
+                    info!("Restarting server in {:?} (attempt {}/{})", backoff, attempts, policy.max_attempts);
+                    tokio::time::sleep(backoff).await;
+                    
+                    handle.set_state(ClientState::Connecting).await;
+                    match handle.restart(config.clone(), inbound.clone()).await.map_err(|e| handle.with_stderr(e)) {
+                        Ok(()) => {
+                            info!("Server restarted");
+                            connected_at = Instant::now();
+                            break;
+                        }
+                        Err(e) => {
+                            error!("Restart attempt {} failed: {}", attempts, e);
+                            handle.set_state(ClientState::Error(format!("Restart failed: {}", e))).await;
+                        }
+                    }
+                }
+            }
+        }));
     }
     
     pub async fn connect(&mut self, config: ServerConfig) -> Result<(), McpClientError> {
         info!("========================================");
         info!("Starting MCP connection process");
+        info!("Transport: {:?}", config.transport);
         info!("Command: {}", config.command);
         info!("Args: {:?}", config.args);
         if let Some(ref cwd) = config.cwd {
@@ -56,31 +367,33 @@ impl ProperMcpClient {
         info!("========================================");
         
         // Update state
-        {
-            let mut state = self.state.write().await;
-            *state = ClientState::Connecting;
-        }
+        self.handle.set_state(ClientState::Connecting).await;
         
         // Create transport
-        info!("Creating StdioTransport...");
-        let transport = match StdioTransport::new(config).await {
+        info!("Creating transport...");
+        let (inbound_tx, inbound_rx) = mpsc::unbounded_channel();
+        self.spawn_dispatcher(inbound_rx);
+        let transport = match connect_transport(config.clone(), inbound_tx.clone(), self.handle.stderr.clone()).await {
             Ok(t) => {
-                info!("StdioTransport created successfully");
+                info!("Transport created successfully");
                 t
             }
             Err(e) => {
-                error!("Failed to create StdioTransport: {:?}", e);
-                let mut state = self.state.write().await;
-                *state = ClientState::Error(format!("Transport error: {}", e));
-                return Err(McpClientError::Transport(e));
+                error!("Failed to create transport: {:?}", e);
+                let e = self.handle.with_stderr(e.into());
+                self.handle.set_state(ClientState::Error(e.to_string())).await;
+                return Err(e);
             }
         };
-        self.transport = Some(Box::new(transport));
+        *self.handle.transport.write().unwrap() = Some(Arc::from(transport));
         
         // Initialize the connection
         info!("Starting initialization sequence...");
-        match self.initialize().await {
+        match self.handle.initialize().await {
             Ok(()) => {
+                self.spawn_tool_refresher().await;
+                self.spawn_resource_watcher().await;
+                self.spawn_supervisor(config, inbound_tx);
                 info!("========================================");
                 info!("MCP CONNECTION ESTABLISHED SUCCESSFULLY");
                 info!("========================================");
@@ -88,27 +401,65 @@ impl ProperMcpClient {
             }
             Err(e) => {
                 error!("Initialization failed: {:?}", e);
-                let mut state = self.state.write().await;
-                *state = ClientState::Error(format!("Initialization failed: {}", e));
+                let e = self.handle.with_stderr(e);
+                self.handle.set_state(ClientState::Error(format!("Initialization failed: {}", e))).await;
                 Err(e)
             }
         }
     }
     
-    async fn initialize(&mut self) -> Result<(), McpClientError> {
-        let transport = self.transport.as_ref()
-            .ok_or(McpClientError::NotConnected)?;
+    /// Closes the connection. For a stdio server, returns the shutdown stage
+    /// that stopped its process.
+    pub async fn disconnect(&mut self) -> Result<Option<ShutdownStage>, McpClientError> {
+        // Stop the supervisor first so closing doesn't look like a crash
+        for task in self.tasks.drain(..) {
+            task.abort();
+        }
+        self.handle.request_handlers.cancel_all("Client disconnected");
+        
+        let transport = self.handle.transport.write().unwrap().take();
+        let stage = match transport {
+            Some(transport) => transport.close().await?,
+            None => None,
+        };
+        
+        self.handle.set_state(ClientState::Disconnected).await;
+        
+        {
+            let mut tools = self.handle.tools.write().await;
+            tools.clear();
+        }
+        
+        self.handle.prompts.write().await.clear();
+        self.handle.resource_subscriptions.write().await.clear();
+        *self.handle.log_level.write().await = None;
+        *self.handle.protocol_version.write().unwrap() = None;
+        
+        {
+            let mut capabilities = self.handle.server_capabilities.write().await;
+            *capabilities = None;
+        }
+        
+        info!("Disconnected from MCP server");
+        Ok(stage)
+    }
+}
+
+impl McpClientHandle {
+    async fn set_state(&self, state: ClientState) {
+        *self.state.write().await = state.clone();
+        self.events.publish(ClientEvent::ConnectionState(state));
+    }
+    
+    async fn initialize(&self) -> Result<(), McpClientError> {
+        let transport = self.transport()?;
         
         info!("Initializing MCP connection");
         
         // Prepare initialization request
         let init_request = InitializeRequest {
-            protocol_version: "2024-11-05".to_string(), // Use latest stable version
-            capabilities: ClientCapabilities {
-                tools: Some(ToolsCapability { list: true }),
-                prompts: Some(PromptsCapability { list: true }),
-                resources: Some(ResourcesCapability { list: true }),
-            },
+            protocol_version: ProtocolVersion::LATEST.to_string(),
+            capabilities: self.request_handlers.capabilities(),
             client_info: ClientInfo {
                 name: "MCP Desktop Client".to_string(),
                 version: "0.1.0".to_string(),
@@ -131,7 +482,7 @@ impl ProperMcpClient {
             }
             Err(e) => {
                 error!("✗ Failed to send initialize request: {:?}", e);
-                return Err(McpClientError::Transport(e));
+                return Err(e.into());
             }
         };
         
@@ -142,6 +493,24 @@ impl ProperMcpClient {
         info!("Server: {} v{}", init_response.server_info.name, init_response.server_info.version);
         info!("Protocol version: {}", init_response.protocol_version);
         
+        // The server answers with our version or one it prefers; we can only
+        // go on if that one is ours too
+        let Some(version) = ProtocolVersion::negotiate(&init_response.protocol_version) else {
+            error!("✗ No common protocol version, disconnecting");
+            let transport = self.transport.write().unwrap().take();
+            if let Some(transport) = transport {
+                let _ = transport.close().await;
+            }
+            return Err(McpClientError::UnsupportedProtocolVersion {
+                server: init_response.protocol_version,
+            });
+        };
+        if version != ProtocolVersion::LATEST {
+            info!("Downgrading to protocol version {}", version);
+        }
+        *self.protocol_version.write().unwrap() = Some(version);
+        transport.set_protocol_version(version);
+        
         // Store server capabilities
         {
             let mut capabilities = self.server_capabilities.write().await;
@@ -149,73 +518,108 @@ impl ProperMcpClient {
         }
         
         // Send initialized notification
-        info!("Sending 'initialized' notification...");
-        match transport.send_notification("initialized", None).await {
-            Ok(()) => info!("✓ 'initialized' notification sent"),
+        let initialized = version.initialized_notification();
+        info!("Sending '{}' notification...", initialized);
+        match transport.send_notification(initialized, None).await {
+            Ok(()) => info!("✓ '{}' notification sent", initialized),
             Err(e) => {
-                error!("✗ Failed to send 'initialized' notification: {:?}", e);
-                return Err(McpClientError::Transport(e));
+                error!("✗ Failed to send '{}' notification: {:?}", initialized, e);
+                return Err(e.into());
             }
         }
         
         // Update state to connected
-        {
-            let mut state = self.state.write().await;
-            *state = ClientState::Connected;
-        }
+        self.set_state(ClientState::Connected).await;
         
         info!("✓ MCP connection initialized successfully");
         info!("Client state updated to: Connected");
         Ok(())
     }
     
+    /// Replaces a connection that ended on its own with a fresh one and
+    /// initializes it.
+    async fn restart(&self, config: ServerConfig, inbound: InboundSender) -> Result<(), McpClientError> {
+        let transport: Arc<dyn Transport> = Arc::from(connect_transport(config, inbound, self.stderr.clone()).await?);
+        let previous = self.transport.write().unwrap().replace(transport.clone());
+        if let Some(previous) = previous {
+            let _ = previous.close().await;
+        }
+        self.reinitialize().await
+    }
+    
+    /// Initializes the connection again after the server lost the session,
+    /// staying in `Connecting` until the handshake is done.
+    async fn renew_session(&self, reason: &str) {
+        warn!("Server lost the session ({}), initializing again", reason);
+        self.set_state(ClientState::Connecting).await;
+        if let Err(e) = self.reinitialize().await {
+            error!("Failed to renew session: {}", e);
+            self.set_state(ClientState::Error(format!("Failed to renew session: {}", e))).await;
+        }
+    }
+    
+    /// Initializes a new server session, then restores what it doesn't know
+    /// about: the tool list, if it was fetched, resource subscriptions and
+    /// the log level.
+    async fn reinitialize(&self) -> Result<(), McpClientError> {
+        let transport = self.transport()?;
+        self.initialize().await?;
+        
+        if !self.tools.read().await.is_empty() {
+            let tools = self.fetch_tools().await?;
+            self.store_tools(tools).await;
+        }
+        let subscriptions: Vec<String> = self.resource_subscriptions.read().await.iter().cloned().collect();
+        for uri in subscriptions {
+            let params = json!(ResourceUriRequest { uri: uri.clone() });
+            if let Err(e) = transport.send_request("resources/subscribe", Some(params)).await {
+                warn!("Failed to resubscribe to resource {}: {}", uri, e);
+            }
+        }
+        if let Some(level) = *self.log_level.read().await {
+            if let Err(e) = transport.send_request("logging/setLevel", Some(json!(SetLevelRequest { level }))).await {
+                warn!("Failed to restore log level: {}", e);
+            }
+        }
+        Ok(())
+    }
+    
     pub async fn list_tools(&self) -> Result<Vec<Tool>, McpClientError> {
         info!("========================================");
         info!("Starting tool discovery process");
         
-        let transport = self.transport.as_ref()
-            .ok_or(McpClientError::NotConnected)?;
-        
-        // Check if server supports tools
-        {
-            let capabilities = self.server_capabilities.read().await;
-            if let Some(ref caps) = *capabilities {
-                info!("Server capabilities: {:?}", caps);
-                if caps.tools.is_none() {
-                    info!("Server does not support tools - returning empty list");
-                    return Ok(Vec::new());
-                }
-                info!("✓ Server supports tools");
-            } else {
-                error!("Server not initialized - no capabilities available");
-                return Err(McpClientError::Protocol("Server not initialized".into()));
-            }
+        self.transport()?;
+        if self.tools_capability().await?.is_none() {
+            info!("Server does not support tools - returning empty list");
+            return Ok(Vec::new());
         }
+        info!("✓ Server supports tools");
         
-        info!("Sending tools/list request...");
+        let tools = self.fetch_tools().await?;
+        self.store_tools(tools.clone()).await;
         
-        // Send tools/list request
-        let response = match transport.send_request("tools/list", None).await {
-            Ok(response) => {
-                info!("✓ Received tools/list response");
-                info!("Response JSON: {}", serde_json::to_string_pretty(&response).unwrap_or_default());
-                response
-            }
-            Err(e) => {
-                error!("✗ Failed to send tools/list request: {:?}", e);
-                return Err(McpClientError::Transport(e));
-            }
-        };
+        info!("========================================");
+        Ok(tools)
+    }
+    
+    /// Lists one page of tools, starting at `cursor`. Does not touch the tool cache.
+    pub async fn list_tools_page(&self, cursor: Option<String>) -> Result<ListToolsResponse, McpClientError> {
+        let transport = self.transport()?;
+        if self.tools_capability().await?.is_none() {
+            return Ok(ListToolsResponse { tools: Vec::new(), next_cursor: None });
+        }
         
-        // Parse response
-        let tools_response: ListToolsResponse = serde_json::from_value(response)
-            .map_err(|e| {
-                error!("✗ Failed to parse tools/list response: {}", e);
-                McpClientError::Protocol(format!("Invalid tools/list response: {}", e))
-            })?;
-        
-        info!("✓ Successfully discovered {} tools", tools_response.tools.len());
-        for (i, tool) in tools_response.tools.iter().enumerate() {
+        Self::send_typed(transport.as_ref(), "tools/list", Some(json!(PaginatedRequest { cursor }))).await
+    }
+    
+    async fn fetch_tools(&self) -> Result<Vec<Tool>, McpClientError> {
+        info!("Sending tools/list requests...");
+        let transport = self.transport()?;
+        let tools: Vec<Tool> = Self::fetch_all::<ListToolsResponse>(transport.as_ref(), "tools/list").await
+            .inspect_err(|e| error!("✗ Failed to list tools: {}", e))?;
+        
+        info!("✓ Successfully discovered {} tools", tools.len());
+        for (i, tool) in tools.iter().enumerate() {
             info!("  Tool {}: {}", i + 1, tool.name);
             if let Some(ref desc) = tool.description {
                 info!("    Description: {}", desc);
@@ -223,64 +627,600 @@ impl ProperMcpClient {
             info!("    Input schema: {:?}", tool.input_schema);
         }
         
-        // Store tools
-        {
-            let mut tools = self.tools.write().await;
-            *tools = tools_response.tools.clone();
+        Ok(tools)
+    }
+    
+    /// Follows `nextCursor` until the list is exhausted. Stops after
+    /// `MAX_LIST_PAGES` pages, or if the server hands back a cursor it already
+    /// sent, and returns what was collected so far.
+    async fn fetch_all<P: Paginated>(transport: &dyn Transport, method: &str) -> Result<Vec<P::Item>, McpClientError> {
+        let mut items = Vec::new();
+        let mut seen = HashSet::new();
+        let mut cursor = None;
+        
+        for page in 1..=MAX_LIST_PAGES {
+            let response: P = Self::send_typed(transport, method, Some(json!(PaginatedRequest { cursor }))).await?;
+            let (page_items, next_cursor) = response.into_page();
+            debug!("{} page {}: {} items", method, page, page_items.len());
+            items.extend(page_items);
+            
+            match next_cursor {
+                None => return Ok(items),
+                Some(next) if !seen.insert(next.clone()) => {
+                    warn!("{} returned cursor '{}' twice, stopping after {} items", method, next, items.len());
+                    return Ok(items);
+                }
+                Some(next) => cursor = Some(next),
+            }
         }
         
-        info!("========================================");
-        Ok(tools_response.tools)
+        warn!("{} has more than {} pages, stopping after {} items", method, MAX_LIST_PAGES, items.len());
+        Ok(items)
     }
     
-    pub async fn call_tool(&self, name: &str, arguments: Option<Value>) -> Result<CallToolResponse, McpClientError> {
-        let transport = self.transport.as_ref()
-            .ok_or(McpClientError::NotConnected)?;
+    /// Replaces the cached tool list and publishes what changed, if anything.
+    async fn store_tools(&self, tools: Vec<Tool>) {
+        let diff = {
+            let mut cached = self.tools.write().await;
+            let diff = ToolListDiff::between(&cached, &tools);
+            *cached = tools;
+            diff
+        };
+        
+        if !diff.is_empty() {
+            info!("Tool list changed: {} added, {} removed, {} changed",
+                diff.added.len(), diff.removed.len(), diff.changed.len());
+            self.events.publish(ClientEvent::ToolsChanged(diff));
+        }
+    }
+    
+    /// Calls a tool with per-call options such as cancellation and progress reporting.
+    pub async fn call_tool_with(
+        &self,
+        name: &str,
+        arguments: Option<Value>,
+        options: ToolCallOptions,
+    ) -> Result<CallToolResponse, McpClientError> {
+        let transport = self.transport()?;
         
         info!("Calling tool: {}", name);
         
+        let tool = self.cached_tool(name).await?;
+        let arguments = match tool {
+            Some(ref tool) => Some(Self::prepare_arguments(tool, arguments)?),
+            None => arguments,
+        };
+        
+        let keepalive = options.reset_timeout_on_progress.then(|| Arc::new(Notify::new()));
+        let progress_forwarder = options.progress_token.as_ref()
+            .map(|token| self.spawn_progress_forwarder(token.clone(), name.to_string(), keepalive.clone()));
+        
         let request = CallToolRequest {
             name: name.to_string(),
             arguments,
+            meta: options.progress_token.map(|token| RequestMeta { progress_token: Some(json!(token)) }),
+        };
+        let request_options = RequestOptions {
+            cancellation: options.cancellation,
+            keepalive,
         };
         
-        let response = transport.send_request("tools/call", Some(json!(request))).await?;
+        let response = transport.send_request_with("tools/call", Some(json!(request)), &request_options).await;
+        if let Some(forwarder) = progress_forwarder {
+            forwarder.abort();
+        }
+        let response = response?;
         
-        let tool_response: CallToolResponse = serde_json::from_value(response)
+        let mut tool_response: CallToolResponse = serde_json::from_value(response)
             .map_err(|e| McpClientError::Protocol(format!("Invalid tools/call response: {}", e)))?;
         
+        // Results from older revisions can't carry structured content or
+        // resource links, so don't read them that way
+        let version = self.protocol_version().unwrap_or(ProtocolVersion::LATEST);
+        if !version.supports_structured_content() {
+            tool_response.structured_content = None;
+        }
+        if !version.supports_resource_links() {
+            for content in &mut tool_response.content {
+                if let ToolContent::ResourceLink(_) = content {
+                    *content = ToolContent::Unknown(serde_json::to_value(&*content)?);
+                }
+            }
+        }
+        let output_schema = tool.as_ref()
+            .and_then(|tool| tool.output_schema.as_ref())
+            .filter(|_| version.supports_structured_content());
+        
+        if tool_response.is_error {
+            info!("Tool {} reported an error", name);
+        } else if let Some(schema) = output_schema {
+            let Some(ref structured) = tool_response.structured_content else {
+                return Err(McpClientError::Protocol(format!(
+                    "Tool '{}' declares an output schema but returned no structured content", name
+                )));
+            };
+            match json_schema::validate(schema, structured) {
+                Ok(()) => {}
+                Err(ValidationError::Violations(violations)) => {
+                    return Err(McpClientError::InvalidToolOutput {
+                        tool: name.to_string(),
+                        violations,
+                    });
+                }
+                Err(ValidationError::UnusableSchema(e)) => {
+                    warn!("Not checking structured content of tool '{}', its output schema can't be used: {}", name, e);
+                }
+            }
+        }
+        
         Ok(tool_response)
     }
     
-    pub async fn get_state(&self) -> ClientState {
-        self.state.read().await.clone()
+    /// Publishes `notifications/progress` for one tool call as
+    /// `ClientEvent::ToolProgress` until aborted.
+    fn spawn_progress_forwarder(&self, token: String, tool: String, keepalive: Option<Arc<Notify>>) -> JoinHandle<()> {
+        let events = self.events.clone();
+        let mut subscription = self.events.subscribe();
+        let token_value = json!(token);
+        tokio::spawn(async move {
+            loop {
+                let params = match subscription.recv().await {
+                    Ok(ClientEvent::Notification(ServerNotification::Progress(params))) => params,
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                if params.progress_token != token_value {
+                    continue;
+                }
+                
+                if let Some(ref keepalive) = keepalive {
+                    keepalive.notify_one();
+                }
+                events.publish(ClientEvent::ToolProgress(ToolProgress {
+                    progress_token: token.clone(),
+                    tool: tool.clone(),
+                    progress: params.progress,
+                    total: params.total,
+                    message: params.message,
+                }));
+            }
+        })
     }
     
-    pub async fn get_tools(&self) -> Vec<Tool> {
-        self.tools.read().await.clone()
+    /// Looks a tool up in the cache. An empty cache means tools were never
+    /// listed, so any name is let through for the server to judge.
+    async fn cached_tool(&self, name: &str) -> Result<Option<Tool>, McpClientError> {
+        let tools = self.tools.read().await;
+        let tool = tools.iter().find(|tool| tool.name == name).cloned();
+        if tool.is_none() && !tools.is_empty() {
+            return Err(McpClientError::UnknownTool(name.to_string()));
+        }
+        Ok(tool)
+    }
+    
+    /// Fills in schema defaults and validates arguments against the tool's
+    /// input schema, unless the schema can't be compiled.
+    fn prepare_arguments(tool: &Tool, arguments: Option<Value>) -> Result<Value, McpClientError> {
+        let schema = serde_json::to_value(&tool.input_schema)?;
+        let mut arguments = arguments.unwrap_or_else(|| json!({}));
+        json_schema::apply_defaults(&schema, &mut arguments);
+        
+        match json_schema::validate(&schema, &arguments) {
+            Ok(()) => {}
+            Err(ValidationError::Violations(violations)) => {
+                return Err(McpClientError::InvalidToolArguments {
+                    tool: tool.name.clone(),
+                    violations,
+                });
+            }
+            // The server still knows what it accepts, so leave it to judge
+            Err(ValidationError::UnusableSchema(e)) => {
+                warn!("Not checking arguments for tool '{}', its input schema can't be used: {}", tool.name, e);
+            }
+        }
+        Ok(arguments)
+    }
+    
+    /// Checks arguments for a tool without calling it, so the UI can flag bad
+    /// fields before submitting. Returns the arguments with defaults filled in.
+    pub async fn validate_tool_arguments(&self, name: &str, arguments: Option<Value>) -> Result<Value, McpClientError> {
+        match self.cached_tool(name).await? {
+            Some(tool) => Self::prepare_arguments(&tool, arguments),
+            None => Ok(arguments.unwrap_or_else(|| json!({}))),
+        }
+    }
+    
+    fn transport(&self) -> Result<Arc<dyn Transport>, McpClientError> {
+        self.transport.read().unwrap().clone().ok_or(McpClientError::NotConnected)
+    }
+    
+    /// Sends a request and deserializes its result.
+    async fn send_typed<T: DeserializeOwned>(
+        transport: &dyn Transport,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<T, McpClientError> {
+        let response = transport.send_request(method, params).await?;
+        serde_json::from_value(response)
+            .map_err(|e| McpClientError::Protocol(format!("Invalid {} response: {}", method, e)))
+    }
+    
+    async fn tools_capability(&self) -> Result<Option<ToolsCapability>, McpClientError> {
+        match *self.server_capabilities.read().await {
+            Some(ref caps) => Ok(caps.tools.clone()),
+            None => Err(McpClientError::Protocol("Server not initialized".into())),
+        }
+    }
+    
+    async fn resources_capability(&self) -> Result<Option<ResourcesCapability>, McpClientError> {
+        match *self.server_capabilities.read().await {
+            Some(ref caps) => Ok(caps.resources.clone()),
+            None => Err(McpClientError::Protocol("Server not initialized".into())),
+        }
     }
     
-    pub async fn disconnect(&mut self) -> Result<(), McpClientError> {
-        if let Some(mut transport) = self.transport.take() {
-            transport.close().await?;
+    pub async fn list_resources(&self) -> Result<Vec<Resource>, McpClientError> {
+        let transport = self.transport()?;
+        if self.resources_capability().await?.is_none() {
+            info!("Server does not support resources - returning empty list");
+            return Ok(Vec::new());
         }
         
-        {
-            let mut state = self.state.write().await;
-            *state = ClientState::Disconnected;
+        Self::fetch_all::<ListResourcesResponse>(transport.as_ref(), "resources/list").await
+    }
+    
+    /// Lists one page of resources, starting at `cursor`.
+    pub async fn list_resources_page(&self, cursor: Option<String>) -> Result<ListResourcesResponse, McpClientError> {
+        let transport = self.transport()?;
+        if self.resources_capability().await?.is_none() {
+            return Ok(ListResourcesResponse { resources: Vec::new(), next_cursor: None });
         }
         
-        {
-            let mut tools = self.tools.write().await;
-            tools.clear();
+        Self::send_typed(transport.as_ref(), "resources/list", Some(json!(PaginatedRequest { cursor }))).await
+    }
+    
+    pub async fn list_resource_templates(&self) -> Result<Vec<ResourceTemplate>, McpClientError> {
+        let transport = self.transport()?;
+        if self.resources_capability().await?.is_none() {
+            return Ok(Vec::new());
         }
         
-        {
-            let mut capabilities = self.server_capabilities.write().await;
-            *capabilities = None;
+        Self::fetch_all::<ListResourceTemplatesResponse>(transport.as_ref(), "resources/templates/list").await
+    }
+    
+    /// Lists one page of resource templates, starting at `cursor`.
+    pub async fn list_resource_templates_page(&self, cursor: Option<String>) -> Result<ListResourceTemplatesResponse, McpClientError> {
+        let transport = self.transport()?;
+        if self.resources_capability().await?.is_none() {
+            return Ok(ListResourceTemplatesResponse { resource_templates: Vec::new(), next_cursor: None });
         }
         
-        info!("Disconnected from MCP server");
+        Self::send_typed(transport.as_ref(), "resources/templates/list", Some(json!(PaginatedRequest { cursor }))).await
+    }
+    
+    pub async fn read_resource(&self, uri: &str) -> Result<ReadResourceResponse, McpClientError> {
+        let transport = self.transport()?;
+        if self.resources_capability().await?.is_none() {
+            return Err(McpClientError::Protocol("Server does not support resources".into()));
+        }
+        
+        info!("Reading resource: {}", uri);
+        Self::send_typed(transport.as_ref(), "resources/read", Some(json!(ResourceUriRequest { uri: uri.to_string() }))).await
+    }
+    
+    /// Subscribes to change notifications for a resource. Updated contents
+    /// are published as `ClientEvent::ResourceUpdated`.
+    pub async fn subscribe_resource(&self, uri: &str) -> Result<(), McpClientError> {
+        let transport = self.transport()?;
+        let supports_subscribe = self.resources_capability().await?
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false);
+        if !supports_subscribe {
+            return Err(McpClientError::Protocol("Server does not support resource subscriptions".into()));
+        }
+        
+        info!("Subscribing to resource: {}", uri);
+        // Track the URI first, the server may report an update before it replies
+        self.resource_subscriptions.write().await.insert(uri.to_string());
+        if let Err(e) = transport.send_request("resources/subscribe", Some(json!(ResourceUriRequest { uri: uri.to_string() }))).await {
+            self.resource_subscriptions.write().await.remove(uri);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+    
+    /// Sets the minimum level of log messages the server sends.
+    pub async fn set_log_level(&self, level: LoggingLevel) -> Result<(), McpClientError> {
+        let transport = self.transport()?;
+        let supports_logging = match *self.server_capabilities.read().await {
+            Some(ref caps) => caps.logging.is_some(),
+            None => return Err(McpClientError::Protocol("Server not initialized".into())),
+        };
+        if !supports_logging {
+            return Err(McpClientError::Protocol("Server does not support logging".into()));
+        }
+        
+        info!("Setting server log level to {:?}", level);
+        transport.send_request("logging/setLevel", Some(json!(SetLevelRequest { level }))).await?;
+        *self.log_level.write().await = Some(level);
+        Ok(())
+    }
+    
+    /// Log messages the server has sent that match `filter`, oldest first.
+    pub fn logs(&self, filter: &LogFilter) -> Vec<LogEntry> {
+        self.server_log.query(filter)
+    }
+    
+    /// Names of the loggers the server has sent messages from.
+    pub fn loggers(&self) -> Vec<String> {
+        self.server_log.loggers()
+    }
+    
+    /// Tells the server its roots changed, so it asks for them again.
+    pub async fn notify_roots_changed(&self) -> Result<(), McpClientError> {
+        let transport = self.transport()?;
+        transport.send_notification("notifications/roots/list_changed", None).await?;
         Ok(())
     }
-}
\ No newline at end of file
+    
+    pub async fn unsubscribe_resource(&self, uri: &str) -> Result<(), McpClientError> {
+        let transport = self.transport()?;
+        if !self.resource_subscriptions.write().await.remove(uri) {
+            return Ok(());
+        }
+        
+        info!("Unsubscribing from resource: {}", uri);
+        transport.send_request("resources/unsubscribe", Some(json!(ResourceUriRequest { uri: uri.to_string() }))).await?;
+        Ok(())
+    }
+    
+    async fn prompts_capability(&self) -> Result<Option<PromptsCapability>, McpClientError> {
+        match *self.server_capabilities.read().await {
+            Some(ref caps) => Ok(caps.prompts.clone()),
+            None => Err(McpClientError::Protocol("Server not initialized".into())),
+        }
+    }
+    
+    /// Lists all prompts. They are remembered so `get_prompt` can check
+    /// their required arguments.
+    pub async fn list_prompts(&self) -> Result<Vec<Prompt>, McpClientError> {
+        let transport = self.transport()?;
+        if self.prompts_capability().await?.is_none() {
+            info!("Server does not support prompts - returning empty list");
+            return Ok(Vec::new());
+        }
+        
+        let prompts = Self::fetch_all::<ListPromptsResponse>(transport.as_ref(), "prompts/list").await?;
+        *self.prompts.write().await = prompts.clone();
+        Ok(prompts)
+    }
+    
+    /// Lists one page of prompts, starting at `cursor`. Listing from the first
+    /// page replaces the remembered prompts, later pages add to them.
+    pub async fn list_prompts_page(&self, cursor: Option<String>) -> Result<ListPromptsResponse, McpClientError> {
+        let transport = self.transport()?;
+        if self.prompts_capability().await?.is_none() {
+            return Ok(ListPromptsResponse { prompts: Vec::new(), next_cursor: None });
+        }
+        
+        let first_page = cursor.is_none();
+        let response: ListPromptsResponse = Self::send_typed(transport.as_ref(), "prompts/list", Some(json!(PaginatedRequest { cursor }))).await?;
+        
+        let mut cached = self.prompts.write().await;
+        if first_page {
+            cached.clear();
+        }
+        cached.extend(response.prompts.iter().cloned());
+        Ok(response)
+    }
+    
+    /// Renders a prompt with the given arguments into the messages the server templates it to.
+    pub async fn get_prompt(&self, name: &str, arguments: HashMap<String, String>) -> Result<GetPromptResponse, McpClientError> {
+        let transport = self.transport()?;
+        if self.prompts_capability().await?.is_none() {
+            return Err(McpClientError::Protocol("Server does not support prompts".into()));
+        }
+        
+        // Prompts that were never listed are left to the server to validate
+        if let Some(prompt) = self.prompts.read().await.iter().find(|p| p.name == name) {
+            let missing: Vec<&str> = prompt.arguments.iter()
+                .filter(|arg| arg.required && !arguments.contains_key(&arg.name))
+                .map(|arg| arg.name.as_str())
+                .collect();
+            if !missing.is_empty() {
+                return Err(McpClientError::InvalidArguments(format!(
+                    "prompt '{}' requires {}", name, missing.join(", ")
+                )));
+            }
+        }
+        
+        info!("Getting prompt: {}", name);
+        let request = GetPromptRequest { name: name.to_string(), arguments };
+        Self::send_typed(transport.as_ref(), "prompts/get", Some(json!(request))).await
+    }
+    
+    pub async fn get_state(&self) -> ClientState {
+        self.state.read().await.clone()
+    }
+    
+    pub async fn get_tools(&self) -> Vec<Tool> {
+        self.tools.read().await.clone()
+    }
+    
+    /// The protocol version agreed on with the server, once initialized.
+    pub fn protocol_version(&self) -> Option<ProtocolVersion> {
+        *self.protocol_version.read().unwrap()
+    }
+    
+    /// The last `limit` lines the server wrote to stderr, or all that are kept.
+    pub fn stderr(&self, limit: Option<usize>) -> Vec<StderrLine> {
+        self.stderr.tail(limit)
+    }
+    
+    /// Appends the end of the server's stderr to a connection error, since
+    /// that is usually where a server explains why it failed.
+    fn with_stderr(&self, error: McpClientError) -> McpClientError {
+        let stderr: Vec<String> = self.stderr.tail(Some(STDERR_TAIL_LINES)).into_iter()
+            .map(|line| line.line)
+            .collect();
+        if stderr.is_empty() {
+            error
+        } else {
+            McpClientError::WithStderr { error: Box::new(error), stderr }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use tokio::sync::Barrier;
+
+    /// Holds every request at a barrier, so requests only complete once the
+    /// expected number are in flight together.
+    struct BarrierTransport {
+        barrier: Barrier,
+    }
+
+    #[async_trait]
+    impl Transport for BarrierTransport {
+        async fn send_request_with(&self, method: &str, _params: Option<Value>, _options: &RequestOptions) -> Result<Value, TransportError> {
+            assert_eq!(method, "tools/call");
+            self.barrier.wait().await;
+            Ok(json!({ "content": [] }))
+        }
+
+        async fn send_notification(&self, _method: &str, _params: Option<Value>) -> Result<(), TransportError> {
+            Ok(())
+        }
+
+        async fn close(&self) -> Result<Option<ShutdownStage>, TransportError> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn tool_calls_through_handles_run_concurrently() {
+        const CALLS: usize = 8;
+        let client = ProperMcpClient::new();
+        let transport = Arc::new(BarrierTransport {
+            barrier: Barrier::new(CALLS + 1),
+        });
+        *client.handle.transport.write().unwrap() = Some(transport.clone());
+
+        let calls: Vec<_> = (0..CALLS)
+            .map(|i| {
+                let handle = client.handle();
+                tokio::spawn(async move {
+                    handle.call_tool_with(&format!("tool-{}", i), None, ToolCallOptions::default()).await
+                })
+            })
+            .collect();
+
+        // Passes the barrier only while all calls are waiting inside the transport
+        tokio::time::timeout(Duration::from_secs(5), transport.barrier.wait())
+            .await
+            .expect("tool calls did not all reach the transport at once");
+        for call in calls {
+            call.await.unwrap().unwrap();
+        }
+    }
+
+    #[test]
+    fn arguments_are_not_checked_against_an_unusable_schema() {
+        let tool: Tool = serde_json::from_value(json!({
+            "name": "open",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "$ref": "https://example.com/schemas/path.json" },
+                    "mode": { "type": "string", "default": "read" },
+                },
+            },
+        })).unwrap();
+
+        let arguments = McpClientHandle::prepare_arguments(&tool, Some(json!({ "path": 42 }))).unwrap();
+        assert_eq!(arguments, json!({ "path": 42, "mode": "read" }));
+    }
+
+    /// Answers every tool call with the same result.
+    struct FixedResultTransport {
+        result: Value,
+    }
+
+    #[async_trait]
+    impl Transport for FixedResultTransport {
+        async fn send_request_with(&self, method: &str, _params: Option<Value>, _options: &RequestOptions) -> Result<Value, TransportError> {
+            assert_eq!(method, "tools/call");
+            Ok(self.result.clone())
+        }
+
+        async fn send_notification(&self, _method: &str, _params: Option<Value>) -> Result<(), TransportError> {
+            Ok(())
+        }
+
+        async fn close(&self) -> Result<Option<ShutdownStage>, TransportError> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn output_is_returned_unchecked_when_the_output_schema_is_unusable() {
+        let client = ProperMcpClient::new();
+        let structured = json!({ "path": "/tmp" });
+        *client.handle.transport.write().unwrap() = Some(Arc::new(FixedResultTransport {
+            result: json!({ "content": [], "structuredContent": structured }),
+        }));
+        *client.handle.tools.write().await = vec![serde_json::from_value(json!({
+            "name": "resolve",
+            "inputSchema": { "type": "object" },
+            "outputSchema": {
+                "type": "object",
+                "properties": { "path": { "$ref": "https://example.com/schemas/path.json" } },
+            },
+        })).unwrap()];
+
+        let response = client.handle().call_tool_with("resolve", None, ToolCallOptions::default()).await.unwrap();
+        assert_eq!(response.structured_content, Some(structured));
+    }
+
+    /// Answers nothing, ending each request only once it is cancelled.
+    struct CancellableTransport;
+
+    #[async_trait]
+    impl Transport for CancellableTransport {
+        async fn send_request_with(&self, _method: &str, _params: Option<Value>, options: &RequestOptions) -> Result<Value, TransportError> {
+            let token = options.cancellation.as_ref().expect("request is not cancellable");
+            Err(TransportError::Cancelled(token.cancelled().await))
+        }
+
+        async fn send_notification(&self, _method: &str, _params: Option<Value>) -> Result<(), TransportError> {
+            Ok(())
+        }
+
+        async fn close(&self) -> Result<Option<ShutdownStage>, TransportError> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelled_tool_call_is_not_a_transport_error() {
+        let client = ProperMcpClient::new();
+        *client.handle.transport.write().unwrap() = Some(Arc::new(CancellableTransport));
+        let token = CancellationToken::new();
+        let options = ToolCallOptions {
+            cancellation: Some(token.clone()),
+            ..Default::default()
+        };
+
+        let handle = client.handle();
+        let call = tokio::spawn(async move { handle.call_tool_with("slow", None, options).await });
+        token.cancel("Cancelled by user");
+
+        match call.await.unwrap() {
+            Err(McpClientError::Cancelled(reason)) => assert_eq!(reason, "Cancelled by user"),
+            other => panic!("expected a cancellation, got {:?}", other.map(|_| ())),
+        }
+    }
+}
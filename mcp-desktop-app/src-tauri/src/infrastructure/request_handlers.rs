@@ -0,0 +1,176 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use tracing::{debug, info, warn};
+
+use crate::domain::json_rpc::{JsonRpcError, JsonRpcId, JsonRpcRequest, JsonRpcResponse};
+use crate::domain::mcp_types::{ClientCapabilities, ElicitationCapability, RootsCapability, SamplingCapability};
+use crate::infrastructure::mcp_transport::CancellationToken;
+
+/// Answers one kind of request sent by the server (`ping`, `roots/list`, ...).
+#[async_trait]
+pub trait RequestHandler: Send + Sync {
+    async fn handle(&self, params: Option<Value>) -> Result<Value, JsonRpcError>;
+}
+
+/// Replies to `ping` with an empty result, as required by the spec.
+pub struct PingHandler;
+
+#[async_trait]
+impl RequestHandler for PingHandler {
+    async fn handle(&self, _params: Option<Value>) -> Result<Value, JsonRpcError> {
+        Ok(json!({}))
+    }
+}
+
+/// Removes a request from a handler's table of requests waiting on the user
+/// when dropped, so the entry goes away however the handler ends, including
+/// when the request is cancelled.
+pub(crate) struct PendingGuard<'a, V> {
+    pending: &'a Mutex<HashMap<String, V>>,
+    request_id: &'a str,
+}
+
+impl<'a, V> PendingGuard<'a, V> {
+    pub fn new(pending: &'a Mutex<HashMap<String, V>>, request_id: &'a str) -> Self {
+        Self { pending, request_id }
+    }
+}
+
+impl<V> Drop for PendingGuard<'_, V> {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(self.request_id);
+    }
+}
+
+/// Routes server requests to the handler registered for their method.
+/// Cloning shares the underlying handler table and the requests in flight.
+#[derive(Clone)]
+pub struct RequestHandlerRegistry {
+    handlers: Arc<RwLock<HashMap<String, Arc<dyn RequestHandler>>>>,
+    in_flight: Arc<Mutex<HashMap<JsonRpcId, CancellationToken>>>,
+}
+
+impl RequestHandlerRegistry {
+    /// Creates a registry with the built-in `ping` handler.
+    pub fn new() -> Self {
+        let registry = Self {
+            handlers: Arc::new(RwLock::new(HashMap::new())),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        };
+        registry.register("ping", Arc::new(PingHandler));
+        registry
+    }
+
+    pub fn register(&self, method: impl Into<String>, handler: Arc<dyn RequestHandler>) {
+        self.handlers.write().unwrap().insert(method.into(), handler);
+    }
+
+    /// The capabilities to advertise in `initialize`: one for each kind of
+    /// server request a handler is registered for. Whoever answers
+    /// `roots/list` is expected to announce changes to the roots.
+    pub fn capabilities(&self) -> ClientCapabilities {
+        let handlers = self.handlers.read().unwrap();
+        ClientCapabilities {
+            roots: handlers.contains_key("roots/list").then_some(RootsCapability { list_changed: Some(true) }),
+            sampling: handlers.contains_key("sampling/createMessage").then(SamplingCapability::default),
+            elicitation: handlers.contains_key("elicitation/create").then(ElicitationCapability::default),
+            experimental: None,
+        }
+    }
+
+    /// Abandons a request the server cancelled with `notifications/cancelled`,
+    /// returning false if it is not in flight.
+    pub fn cancel(&self, id: &JsonRpcId, reason: &str) -> bool {
+        match self.in_flight.lock().unwrap().get(id) {
+            Some(token) => {
+                token.cancel(reason);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Abandons every request in flight, for when the connection goes away.
+    pub fn cancel_all(&self, reason: &str) {
+        for token in self.in_flight.lock().unwrap().values() {
+            token.cancel(reason);
+        }
+    }
+
+    /// Runs the handler for a request until it answers or the request is
+    /// cancelled. A cancelled request's handler is dropped and it gets no
+    /// response, as the spec asks.
+    pub async fn dispatch(&self, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+        let handler = self.handlers.read().unwrap().get(&request.method).cloned();
+
+        let Some(handler) = handler else {
+            warn!("No handler for server request: {}", request.method);
+            return Some(JsonRpcResponse::error(request.id, JsonRpcError::method_not_found(&request.method)));
+        };
+
+        debug!("Dispatching server request: {} with ID: {:?}", request.method, request.id);
+        let token = CancellationToken::new();
+        self.in_flight.lock().unwrap().insert(request.id.clone(), token.clone());
+        let result = tokio::select! {
+            result = handler.handle(request.params) => Some(result),
+            reason = token.cancelled() => {
+                info!("Server request {} with ID {:?} cancelled: {}", request.method, request.id, reason);
+                None
+            }
+        };
+        self.in_flight.lock().unwrap().remove(&request.id);
+
+        match result? {
+            Ok(result) => Some(JsonRpcResponse::success(request.id, result)),
+            Err(error) => {
+                warn!("Handler for {} failed: {}", request.method, error.message);
+                Some(JsonRpcResponse::error(request.id, error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::Notify;
+
+    /// Never answers, so requests only end by being cancelled.
+    struct UnansweredHandler {
+        started: Notify,
+    }
+
+    #[async_trait]
+    impl RequestHandler for UnansweredHandler {
+        async fn handle(&self, _params: Option<Value>) -> Result<Value, JsonRpcError> {
+            self.started.notify_one();
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelled_request_gets_no_response() {
+        let handler = Arc::new(UnansweredHandler { started: Notify::new() });
+        let registry = RequestHandlerRegistry::new();
+        registry.register("sampling/createMessage", handler.clone());
+
+        let id = JsonRpcId::Number(1);
+        let response = tokio::spawn({
+            let registry = registry.clone();
+            let request = JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: id.clone(),
+                method: "sampling/createMessage".to_string(),
+                params: None,
+            };
+            async move { registry.dispatch(request).await }
+        });
+        handler.started.notified().await;
+
+        assert!(registry.cancel(&id, "No longer needed"));
+        assert!(response.await.unwrap().is_none());
+        assert!(!registry.cancel(&id, "No longer needed"));
+    }
+}
@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+use reqwest::Url;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::domain::json_rpc::JsonRpcError;
+use crate::domain::mcp_types::{ListRootsResponse, Root};
+use crate::infrastructure::request_handlers::RequestHandler;
+
+#[derive(Error, Debug)]
+pub enum RootsError {
+    #[error("Not a directory: {0}")]
+    NotADirectory(String),
+    #[error("Failed to save roots: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to save roots: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// The roots of every server configuration, keyed by server id and saved to
+/// a JSON file so they survive restarts of the app.
+pub struct RootsManager {
+    path: PathBuf,
+    roots: Mutex<HashMap<String, Vec<Root>>>,
+}
+
+impl RootsManager {
+    /// Loads the roots saved at `path`, starting empty if there are none.
+    pub fn load(path: PathBuf) -> Self {
+        let roots = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable roots file {}: {}", path.display(), e);
+                HashMap::new()
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(e) => {
+                warn!("Failed to read roots file {}: {}", path.display(), e);
+                HashMap::new()
+            }
+        };
+
+        Self {
+            path,
+            roots: Mutex::new(roots),
+        }
+    }
+
+    pub fn list(&self, server_id: &str) -> Vec<Root> {
+        self.roots.lock().unwrap().get(server_id).cloned().unwrap_or_default()
+    }
+
+    /// Adds a local directory as a root, replacing any root with the same
+    /// URI. Without a name the directory's own name is used.
+    pub fn add(&self, server_id: &str, path: &str, name: Option<String>) -> Result<Root, RootsError> {
+        let root = directory_root(Path::new(path), name)?;
+        self.update(server_id, |roots| {
+            roots.retain(|existing| existing.uri != root.uri);
+            roots.push(root.clone());
+        })?;
+        info!("Added root {} for server '{}'", root.uri, server_id);
+        Ok(root)
+    }
+
+    /// Removes a root by URI, returning false if the server has no such root.
+    pub fn remove(&self, server_id: &str, uri: &str) -> Result<bool, RootsError> {
+        let mut removed = false;
+        self.update(server_id, |roots| {
+            let before = roots.len();
+            roots.retain(|existing| existing.uri != uri);
+            removed = roots.len() != before;
+        })?;
+        if removed {
+            info!("Removed root {} for server '{}'", uri, server_id);
+        }
+        Ok(removed)
+    }
+
+    /// Applies a change to one server's roots, keeping it only if it was saved.
+    fn update(&self, server_id: &str, change: impl FnOnce(&mut Vec<Root>)) -> Result<(), RootsError> {
+        let mut roots = self.roots.lock().unwrap();
+        let mut updated = roots.clone();
+        let server_roots = updated.entry(server_id.to_string()).or_default();
+        change(server_roots);
+        if server_roots.is_empty() {
+            updated.remove(server_id);
+        }
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, serde_json::to_vec_pretty(&updated)?)?;
+        *roots = updated;
+        Ok(())
+    }
+}
+
+fn directory_root(path: &Path, name: Option<String>) -> Result<Root, RootsError> {
+    let not_a_directory = || RootsError::NotADirectory(path.display().to_string());
+    let path = path.canonicalize().map_err(|_| not_a_directory())?;
+    if !path.is_dir() {
+        return Err(not_a_directory());
+    }
+    let uri = Url::from_file_path(&path).map_err(|_| not_a_directory())?;
+
+    Ok(Root {
+        uri: uri.to_string(),
+        name: name.or_else(|| path.file_name().map(|name| name.to_string_lossy().into_owned())),
+    })
+}
+
+/// Answers `roots/list` with the roots of one server configuration.
+pub struct RootsHandler {
+    manager: Arc<RootsManager>,
+    server_id: String,
+}
+
+impl RootsHandler {
+    pub fn new(manager: Arc<RootsManager>, server_id: String) -> Self {
+        Self { manager, server_id }
+    }
+}
+
+#[async_trait]
+impl RequestHandler for RootsHandler {
+    async fn handle(&self, _params: Option<Value>) -> Result<Value, JsonRpcError> {
+        Ok(json!(ListRootsResponse {
+            roots: self.manager.list(&self.server_id),
+        }))
+    }
+}
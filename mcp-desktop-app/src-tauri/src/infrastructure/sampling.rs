@@ -0,0 +1,198 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::oneshot;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::json_rpc::JsonRpcError;
+use crate::domain::mcp_types::{CreateMessageRequest, CreateMessageResult, Role, ToolContent};
+use crate::infrastructure::events::{ClientEvent, EventBus, SamplingRequest};
+use crate::infrastructure::request_handlers::{PendingGuard, RequestHandler};
+
+/// Error code servers get when the user rejects a sampling request.
+const USER_REJECTED: i32 = -1;
+
+/// How long a request waits for the user before it is rejected.
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Error, Debug)]
+pub enum SamplingError {
+    #[error("Sampling provider failed: {0}")]
+    Provider(String),
+}
+
+/// Generates the messages servers ask for with `sampling/createMessage`.
+/// Implementations wrap a model API; they only see requests the user approved.
+/// The providers a server can be configured to sample with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplingProviderKind {
+    /// `MockSamplingProvider`.
+    Mock,
+}
+
+#[async_trait]
+pub trait SamplingProvider: Send + Sync {
+    async fn create_message(&self, request: &CreateMessageRequest) -> Result<CreateMessageResult, SamplingError>;
+}
+
+/// Answers with the last text message echoed back, cut to `max_tokens`
+/// words, so server authors can exercise sampling flows without a model.
+/// Fails when there is no text message.
+pub struct MockSamplingProvider;
+
+impl MockSamplingProvider {
+    pub const MODEL: &'static str = "mock";
+}
+
+#[async_trait]
+impl SamplingProvider for MockSamplingProvider {
+    async fn create_message(&self, request: &CreateMessageRequest) -> Result<CreateMessageResult, SamplingError> {
+        let prompt = request.messages.iter().rev()
+            .find_map(|message| match &message.content {
+                ToolContent::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .ok_or_else(|| SamplingError::Provider("No text message to respond to".to_string()))?;
+
+        let response = format!("Mock response to: {}", prompt);
+        let words: Vec<&str> = response.split_whitespace().collect();
+        let max_tokens = request.max_tokens as usize;
+        let (text, stop_reason) = if words.len() > max_tokens {
+            (words[..max_tokens].join(" "), "maxTokens")
+        } else {
+            (words.join(" "), "endTurn")
+        };
+
+        Ok(CreateMessageResult {
+            role: Role::Assistant,
+            content: ToolContent::Text { text, annotations: None },
+            model: Self::MODEL.to_string(),
+            stop_reason: Some(stop_reason.to_string()),
+        })
+    }
+}
+
+/// Answers `sampling/createMessage`. Each request is announced as
+/// `ClientEvent::SamplingRequested` and waits for the user to approve or
+/// reject it through `respond` before anything is sent to the provider.
+/// Requests the user doesn't answer within `APPROVAL_TIMEOUT` are rejected.
+pub struct SamplingHandler {
+    provider: Arc<dyn SamplingProvider>,
+    events: EventBus,
+    pending: Mutex<HashMap<String, oneshot::Sender<bool>>>,
+}
+
+impl SamplingHandler {
+    pub fn new(provider: Arc<dyn SamplingProvider>, events: EventBus) -> Self {
+        Self {
+            provider,
+            events,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Approves or rejects a pending request, returning false if there is
+    /// no request with this id waiting.
+    pub fn respond(&self, request_id: &str, approved: bool) -> bool {
+        match self.pending.lock().unwrap().remove(request_id) {
+            Some(decision) => decision.send(approved).is_ok(),
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+impl RequestHandler for SamplingHandler {
+    async fn handle(&self, params: Option<Value>) -> Result<Value, JsonRpcError> {
+        let request: CreateMessageRequest = serde_json::from_value(params.unwrap_or(Value::Null))
+            .map_err(|e| JsonRpcError::invalid_params(format!("Invalid sampling request: {}", e)))?;
+
+        let request_id = Uuid::new_v4().to_string();
+        let (decision, approval) = oneshot::channel();
+        self.pending.lock().unwrap().insert(request_id.clone(), decision);
+        let _pending = PendingGuard::new(&self.pending, &request_id);
+        self.events.publish(ClientEvent::SamplingRequested(SamplingRequest {
+            request_id: request_id.clone(),
+            request: request.clone(),
+        }));
+
+        // A dropped decision means the request can no longer be answered by the user
+        match tokio::time::timeout(APPROVAL_TIMEOUT, approval).await {
+            Ok(Ok(true)) => {}
+            Ok(_) => {
+                info!("Sampling request {} rejected", request_id);
+                return Err(JsonRpcError::new(USER_REJECTED, "User rejected sampling request"));
+            }
+            Err(_) => {
+                info!("Sampling request {} timed out waiting for the user", request_id);
+                return Err(JsonRpcError::new(USER_REJECTED, "User did not respond to sampling request"));
+            }
+        }
+
+        info!("Sampling request {} approved", request_id);
+        let result = self.provider.create_message(&request).await
+            .map_err(|e| JsonRpcError::internal_error(e.to_string()))?;
+        Ok(json!(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::task::JoinHandle;
+
+    /// Sends a sampling request to the handler and returns the user's id for it
+    /// along with the server's eventual answer.
+    async fn request_sampling(handler: &Arc<SamplingHandler>, events: &EventBus) -> (String, JoinHandle<Result<Value, JsonRpcError>>) {
+        let mut subscription = events.subscribe();
+        let request = tokio::spawn({
+            let handler = handler.clone();
+            async move {
+                handler.handle(Some(json!({
+                    "messages": [{ "role": "user", "content": { "type": "text", "text": "Summarize the file" } }],
+                    "maxTokens": 100,
+                }))).await
+            }
+        });
+        match subscription.recv().await.unwrap() {
+            ClientEvent::SamplingRequested(sampling) => (sampling.request_id, request),
+            _ => panic!("expected a sampling request event"),
+        }
+    }
+
+    fn handler() -> (Arc<SamplingHandler>, EventBus) {
+        let events = EventBus::new();
+        (Arc::new(SamplingHandler::new(Arc::new(MockSamplingProvider), events.clone())), events)
+    }
+
+    #[tokio::test]
+    async fn approved_request_is_sent_to_the_provider() {
+        let (handler, events) = handler();
+        let (request_id, answer) = request_sampling(&handler, &events).await;
+
+        assert!(handler.respond(&request_id, true));
+        let result: CreateMessageResult = serde_json::from_value(answer.await.unwrap().unwrap()).unwrap();
+        assert_eq!(result.model, MockSamplingProvider::MODEL);
+        match result.content {
+            ToolContent::Text { text, .. } => assert_eq!(text, "Mock response to: Summarize the file"),
+            _ => panic!("expected a text response"),
+        }
+        assert!(!handler.respond(&request_id, true));
+    }
+
+    #[tokio::test]
+    async fn rejected_request_returns_an_error() {
+        let (handler, events) = handler();
+        let (request_id, answer) = request_sampling(&handler, &events).await;
+
+        assert!(handler.respond(&request_id, false));
+        let error = answer.await.unwrap().unwrap_err();
+        assert_eq!(error.code, USER_REJECTED);
+    }
+}
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, VecDeque};
+use std::sync::Mutex;
+
+use crate::domain::mcp_types::LoggingLevel;
+use crate::domain::notifications::LoggingMessageParams;
+
+/// Entries kept per server; older entries are dropped first.
+const SERVER_LOG_CAPACITY: usize = 1000;
+
+/// One log message a server sent with `notifications/message`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub level: LoggingLevel,
+    pub logger: Option<String>,
+    pub data: Value,
+}
+
+/// Which log entries to return. Fields left unset don't filter.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LogFilter {
+    /// Only entries at this level or more severe.
+    #[serde(default)]
+    pub min_level: Option<LoggingLevel>,
+    #[serde(default)]
+    pub logger: Option<String>,
+    /// Only the last `limit` matching entries.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Bounded store of a server's log messages. Like the stderr log it
+/// outlives restarts of the process.
+pub struct ServerLog {
+    entries: Mutex<VecDeque<LogEntry>>,
+}
+
+impl ServerLog {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn push(&self, message: LoggingMessageParams) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == SERVER_LOG_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(LogEntry {
+            timestamp: Utc::now(),
+            level: message.level,
+            logger: message.logger,
+            data: message.data,
+        });
+    }
+
+    /// The entries matching `filter`, oldest first.
+    pub fn query(&self, filter: &LogFilter) -> Vec<LogEntry> {
+        let entries = self.entries.lock().unwrap();
+        let mut matching: Vec<LogEntry> = entries.iter()
+            .filter(|entry| filter.min_level.is_none_or(|level| entry.level >= level))
+            .filter(|entry| filter.logger.is_none() || entry.logger == filter.logger)
+            .cloned()
+            .collect();
+        if let Some(limit) = filter.limit {
+            matching.drain(..matching.len().saturating_sub(limit));
+        }
+        matching
+    }
+
+    /// Names of the loggers that have sent messages, sorted.
+    pub fn loggers(&self) -> Vec<String> {
+        let entries = self.entries.lock().unwrap();
+        let loggers: BTreeSet<&String> = entries.iter().filter_map(|entry| entry.logger.as_ref()).collect();
+        loggers.into_iter().cloned().collect()
+    }
+}
@@ -0,0 +1,116 @@
+/// A single Server-Sent Event as defined by the HTML event-stream format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub data: String,
+    pub id: Option<String>,
+    pub retry: Option<u64>,
+}
+
+impl SseEvent {
+    /// The event type, defaulting to `message` when the stream did not name one.
+    pub fn event_type(&self) -> &str {
+        self.event.as_deref().unwrap_or("message")
+    }
+}
+
+/// Incremental decoder for `text/event-stream` bodies. Feed it raw chunks as
+/// they arrive; it returns every event completed by that chunk.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    /// Bytes of the current incomplete line. Lines are only decoded once
+    /// complete, so a character split across chunks stays intact.
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+    id: Option<String>,
+    retry: Option<u64>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+            if let Some(event) = self.process_line(line) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Flushes an event left pending when the stream ends without a trailing blank line.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        let rest = std::mem::take(&mut self.buffer);
+        if !rest.is_empty() {
+            self.process_line(String::from_utf8_lossy(&rest).trim_end_matches('\r'));
+        }
+        self.dispatch()
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            // Comment / keep-alive
+            return None;
+        }
+
+        let (field, value) = match line.find(':') {
+            Some(idx) => {
+                let value = &line[idx + 1..];
+                (&line[..idx], value.strip_prefix(' ').unwrap_or(value))
+            }
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            "id" => self.id = Some(value.to_string()),
+            "retry" => self.retry = value.parse().ok(),
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        if self.data.is_empty() {
+            self.event = None;
+            return None;
+        }
+        Some(SseEvent {
+            event: self.event.take(),
+            data: std::mem::take(&mut self.data).join("\n"),
+            id: self.id.clone(),
+            retry: self.retry,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_characters_split_across_chunks() {
+        let body = "data: {\"text\":\"caf\u{e9}\"}\n\n".as_bytes();
+        let split = body.iter().position(|&byte| byte == 0xc3).unwrap() + 1;
+
+        let mut decoder = SseDecoder::new();
+        assert!(decoder.feed(&body[..split]).is_empty());
+        let events = decoder.feed(&body[split..]);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "{\"text\":\"caf\u{e9}\"}");
+    }
+}
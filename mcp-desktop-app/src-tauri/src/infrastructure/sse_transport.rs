@@ -0,0 +1,407 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::header::{HeaderMap, ACCEPT};
+use reqwest::Url;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::domain::json_rpc::{JsonRpcId, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest};
+use crate::infrastructure::http_transport::build_header_map;
+use crate::infrastructure::mcp_transport::{
+    await_response, route_request, InboundMessage, InboundSender, PendingRequests, RequestOptions, ShutdownStage,
+    TimeoutConfig, Transport, TransportError,
+};
+use crate::infrastructure::sse::{SseDecoder, SseEvent};
+
+const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(30);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Legacy MCP HTTP+SSE transport (protocol revision 2024-11-05). The client
+/// holds a GET stream open; the server announces a POST endpoint with an
+/// `endpoint` event and delivers all responses over the stream.
+pub struct SseTransport {
+    shared: Arc<SseShared>,
+    reader: Mutex<Option<JoinHandle<()>>>,
+}
+
+struct SseShared {
+    client: reqwest::Client,
+    url: Url,
+    headers: HeaderMap,
+    endpoint: watch::Sender<Option<Url>>,
+    pending: PendingRequests,
+    timeouts: TimeoutConfig,
+    inbound: InboundSender,
+}
+
+impl SseTransport {
+    pub async fn new(
+        url: String,
+        headers: HashMap<String, String>,
+        timeouts: TimeoutConfig,
+        inbound: InboundSender,
+    ) -> Result<Self, TransportError> {
+        info!("Using HTTP+SSE transport: {}", url);
+
+        let url = Url::parse(&url)
+            .map_err(|e| TransportError::Http(format!("Invalid SSE URL '{}': {}", url, e)))?;
+        let (endpoint, _) = watch::channel(None);
+        let shared = Arc::new(SseShared {
+            client: reqwest::Client::new(),
+            url,
+            headers: build_header_map(headers)?,
+            endpoint,
+            pending: PendingRequests::default(),
+            timeouts,
+            inbound,
+        });
+
+        let response = shared.open_stream().await?;
+        let reader = tokio::spawn({
+            let shared = shared.clone();
+            async move { shared.run(response).await }
+        });
+
+        // Nothing can be sent until the server tells us where to POST
+        if let Err(e) = shared.wait_for_endpoint().await {
+            reader.abort();
+            return Err(e);
+        }
+
+        Ok(Self {
+            shared,
+            reader: Mutex::new(Some(reader)),
+        })
+    }
+}
+
+impl SseShared {
+    async fn open_stream(&self) -> Result<reqwest::Response, TransportError> {
+        let response = self.client.get(self.url.clone())
+            .headers(self.headers.clone())
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(TransportError::Http(format!("Server returned {} for SSE stream", response.status())));
+        }
+        Ok(response)
+    }
+
+    async fn wait_for_endpoint(&self) -> Result<Url, TransportError> {
+        let mut rx = self.endpoint.subscribe();
+        let endpoint = tokio::time::timeout(ENDPOINT_TIMEOUT, async {
+            rx.wait_for(|endpoint| endpoint.is_some()).await
+                .map(|endpoint| endpoint.clone())
+                .map_err(|_| TransportError::ChannelClosed)
+        }).await;
+
+        match endpoint {
+            Ok(Ok(Some(endpoint))) => Ok(endpoint),
+            Ok(Ok(None)) | Ok(Err(_)) => Err(TransportError::ChannelClosed),
+            Err(_) => Err(TransportError::Http(format!(
+                "Server did not announce a message endpoint within {}s", ENDPOINT_TIMEOUT.as_secs()
+            ))),
+        }
+    }
+
+    /// Reads the event stream, reconnecting with exponential backoff whenever it drops.
+    async fn run(self: &Arc<Self>, mut response: reqwest::Response) {
+        loop {
+            match self.consume(response).await {
+                Ok(()) => warn!("SSE stream closed by server"),
+                Err(e) => warn!("SSE stream failed: {}", e),
+            }
+
+            // The server session is bound to the stream, so requests sent
+            // on it will never be answered
+            self.endpoint.send_replace(None);
+            self.pending.clear();
+
+            let mut backoff = INITIAL_BACKOFF;
+            response = loop {
+                info!("Reconnecting to SSE stream in {:?}", backoff);
+                tokio::time::sleep(backoff).await;
+                match self.open_stream().await {
+                    Ok(response) => break response,
+                    Err(e) => warn!("SSE reconnect failed: {}", e),
+                }
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            };
+            info!("SSE stream reconnected");
+
+            // The new stream is a new server session that has not been initialized
+            let _ = self.inbound.send(InboundMessage::SessionLost("SSE stream reconnected".to_string()));
+        }
+    }
+
+    async fn consume(self: &Arc<Self>, response: reqwest::Response) -> Result<(), TransportError> {
+        let mut decoder = SseDecoder::new();
+        let mut stream = response.bytes_stream();
+
+        while let Some(chunk) = stream.next().await {
+            for event in decoder.feed(&chunk?) {
+                self.handle_event(event);
+            }
+        }
+        if let Some(event) = decoder.finish() {
+            self.handle_event(event);
+        }
+        Ok(())
+    }
+
+    fn handle_event(self: &Arc<Self>, event: SseEvent) {
+        match event.event_type() {
+            "endpoint" => match self.url.join(event.data.trim()) {
+                Ok(endpoint) if endpoint.origin() == self.url.origin() => {
+                    info!("Server announced message endpoint: {}", endpoint);
+                    self.endpoint.send_replace(Some(endpoint));
+                }
+                Ok(endpoint) => error!("Ignoring message endpoint on a different origin: {}", endpoint),
+                Err(e) => error!("Invalid message endpoint '{}': {}", event.data, e),
+            },
+            "message" => {
+                debug!("Received: {}", event.data);
+                match serde_json::from_str::<JsonRpcMessage>(&event.data) {
+                    Ok(message) => self.dispatch(message),
+                    Err(e) => error!("Failed to parse JSON-RPC message: {} - Raw data: {}", e, event.data),
+                }
+            }
+            other => debug!("Ignoring SSE event of type '{}'", other),
+        }
+    }
+
+    fn dispatch(self: &Arc<Self>, message: JsonRpcMessage) {
+        match message {
+            JsonRpcMessage::Response(response) => {
+                info!("Received response for request ID: {:?}", response.id);
+                self.pending.complete(response);
+            }
+            JsonRpcMessage::Notification(notification) => {
+                debug!("Received notification: {}", notification.method);
+                if self.inbound.send(InboundMessage::Notification(notification)).is_err() {
+                    warn!("Dropping notification, client is no longer listening");
+                }
+            }
+            JsonRpcMessage::Request(request) => {
+                info!("Received request from server: {} with ID: {:?}", request.method, request.id);
+                let shared = self.clone();
+                tokio::spawn(async move {
+                    let Some(response) = route_request(shared.inbound.clone(), request).await else {
+                        return;
+                    };
+                    if let Err(e) = shared.post(&JsonRpcMessage::Response(response)).await {
+                        error!("Failed to send response to server request: {}", e);
+                    }
+                });
+            }
+        }
+    }
+
+    async fn post(&self, message: &JsonRpcMessage) -> Result<(), TransportError> {
+        let endpoint = self.wait_for_endpoint().await?;
+        debug!("POST {}: {}", endpoint, serde_json::to_string(message).unwrap_or_default());
+
+        let response = self.client.post(endpoint)
+            .headers(self.headers.clone())
+            .json(message)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(TransportError::Http(format!("Server returned {}: {}", status, body)));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Transport for SseTransport {
+    async fn send_request_with(&self, method: &str, params: Option<Value>, options: &RequestOptions) -> Result<Value, TransportError> {
+        let id = Uuid::new_v4().to_string();
+        let deadline = self.shared.timeouts.deadline_for(method, params.as_ref());
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+            id: JsonRpcId::String(id.clone()),
+        };
+
+        let rx = self.shared.pending.register(id.clone());
+        if let Err(e) = self.shared.post(&JsonRpcMessage::Request(request)).await {
+            self.shared.pending.remove(&id);
+            return Err(e);
+        }
+
+        // The response arrives on the event stream
+        await_response(self, &self.shared.pending, method, &id, rx, options, deadline).await
+    }
+
+    async fn send_notification(&self, method: &str, params: Option<Value>) -> Result<(), TransportError> {
+        let notification = JsonRpcNotification {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+        };
+
+        self.shared.post(&JsonRpcMessage::Notification(notification)).await
+    }
+
+    async fn close(&self) -> Result<Option<ShutdownStage>, TransportError> {
+        if let Some(reader) = self.reader.lock().unwrap().take() {
+            reader.abort();
+        }
+        self.shared.endpoint.send_replace(None);
+        self.shared.pending.clear();
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::json_rpc::JsonRpcResponse;
+    use axum::extract::{Query, State};
+    use axum::http::StatusCode;
+    use axum::response::sse::{Event, Sse};
+    use axum::routing::{get, post};
+    use axum::Router;
+    use serde_json::json;
+    use std::convert::Infallible;
+    use tokio::sync::mpsc;
+    use tokio_stream::wrappers::UnboundedReceiverStream;
+
+    type EventSender = mpsc::UnboundedSender<Result<Event, Infallible>>;
+
+    /// In-process stand-in for an HTTP+SSE server. Every stream is a new
+    /// session whose message endpoint carries the session number; requests
+    /// are answered with their method echoed back over the stream.
+    #[derive(Default)]
+    struct FakeServer {
+        sessions: Mutex<u32>,
+        stream: Mutex<Option<EventSender>>,
+        /// Session of every POST, in order.
+        posted_sessions: Mutex<Vec<String>>,
+        /// Responses the client posted to server requests.
+        responses: Mutex<Vec<JsonRpcResponse>>,
+    }
+
+    impl FakeServer {
+        fn send(&self, message: Value) {
+            let event = Event::default().event("message").data(message.to_string());
+            self.stream.lock().unwrap().as_ref().expect("no open stream").send(Ok(event)).unwrap();
+        }
+    }
+
+    async fn handle_stream(State(server): State<Arc<FakeServer>>) -> Sse<UnboundedReceiverStream<Result<Event, Infallible>>> {
+        let session = {
+            let mut sessions = server.sessions.lock().unwrap();
+            *sessions += 1;
+            *sessions
+        };
+        let (events, stream) = mpsc::unbounded_channel();
+        events.send(Ok(Event::default().event("endpoint").data(format!("/messages?session={}", session)))).unwrap();
+        *server.stream.lock().unwrap() = Some(events);
+        Sse::new(UnboundedReceiverStream::new(stream))
+    }
+
+    async fn handle_message(
+        State(server): State<Arc<FakeServer>>,
+        Query(query): Query<HashMap<String, String>>,
+        body: String,
+    ) -> StatusCode {
+        server.posted_sessions.lock().unwrap().push(query["session"].clone());
+        match serde_json::from_str::<JsonRpcMessage>(&body).unwrap() {
+            JsonRpcMessage::Request(request) => server.send(json!({
+                "jsonrpc": "2.0",
+                "id": request.id,
+                "result": { "echo": request.method },
+            })),
+            JsonRpcMessage::Response(response) => server.responses.lock().unwrap().push(response),
+            JsonRpcMessage::Notification(_) => {}
+        }
+        StatusCode::ACCEPTED
+    }
+
+    async fn start(server: Arc<FakeServer>) -> (SseTransport, mpsc::UnboundedReceiver<InboundMessage>) {
+        let app = Router::new()
+            .route("/sse", get(handle_stream))
+            .route("/messages", post(handle_message))
+            .with_state(server);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/sse", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let (inbound, inbound_rx) = mpsc::unbounded_channel();
+        let transport = SseTransport::new(url, HashMap::new(), TimeoutConfig::default(), inbound).await.unwrap();
+        (transport, inbound_rx)
+    }
+
+    async fn next_inbound(inbound: &mut mpsc::UnboundedReceiver<InboundMessage>) -> InboundMessage {
+        tokio::time::timeout(Duration::from_secs(5), inbound.recv()).await
+            .expect("no inbound message")
+            .expect("inbound channel closed")
+    }
+
+    #[tokio::test]
+    async fn responses_arrive_over_the_stream() {
+        let server = Arc::new(FakeServer::default());
+        let (transport, _inbound) = start(server.clone()).await;
+
+        let result = transport.send_request("tools/list", None).await.unwrap();
+        assert_eq!(result, json!({ "echo": "tools/list" }));
+        assert_eq!(*server.posted_sessions.lock().unwrap(), vec!["1".to_string()]);
+        transport.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn server_requests_are_answered_by_post() {
+        let server = Arc::new(FakeServer::default());
+        let (transport, mut inbound) = start(server.clone()).await;
+
+        server.send(json!({ "jsonrpc": "2.0", "id": 7, "method": "ping" }));
+        let InboundMessage::Request { request, responder } = next_inbound(&mut inbound).await else {
+            panic!("expected the server's request");
+        };
+        assert_eq!(request.method, "ping");
+        responder.send(Some(JsonRpcResponse::success(request.id, json!({})))).unwrap();
+
+        let response = loop {
+            if let Some(response) = server.responses.lock().unwrap().pop() {
+                break response;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        assert_eq!(response.id, JsonRpcId::Number(7));
+        transport.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reconnect_starts_a_new_session() {
+        let server = Arc::new(FakeServer::default());
+        let (transport, mut inbound) = start(server.clone()).await;
+        transport.send_request("initialize", None).await.unwrap();
+
+        // Closing the stream ends the server session
+        server.stream.lock().unwrap().take();
+        loop {
+            if let InboundMessage::SessionLost(_) = next_inbound(&mut inbound).await {
+                break;
+            }
+        }
+
+        transport.send_request("initialize", None).await.unwrap();
+        assert_eq!(*server.posted_sessions.lock().unwrap(), vec!["1".to_string(), "2".to_string()]);
+        transport.close().await.unwrap();
+    }
+}
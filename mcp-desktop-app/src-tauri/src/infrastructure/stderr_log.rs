@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::infrastructure::events::{ClientEvent, EventBus};
+
+/// Lines kept per server; older lines are dropped first.
+const STDERR_CAPACITY: usize = 1000;
+
+/// One line a server process wrote to stderr.
+#[derive(Debug, Clone, Serialize)]
+pub struct StderrLine {
+    pub timestamp: DateTime<Utc>,
+    pub line: String,
+}
+
+/// Bounded buffer of a server's stderr output. Outlives restarts of the
+/// process, so a crash can be diagnosed from what the previous process wrote.
+pub struct StderrLog {
+    lines: Mutex<VecDeque<StderrLine>>,
+    events: EventBus,
+}
+
+impl StderrLog {
+    pub fn new(events: EventBus) -> Self {
+        Self {
+            lines: Mutex::new(VecDeque::new()),
+            events,
+        }
+    }
+
+    /// Records a line and publishes it as `ClientEvent::Stderr`.
+    pub fn push(&self, line: String) {
+        let line = StderrLine {
+            timestamp: Utc::now(),
+            line,
+        };
+        {
+            let mut lines = self.lines.lock().unwrap();
+            if lines.len() == STDERR_CAPACITY {
+                lines.pop_front();
+            }
+            lines.push_back(line.clone());
+        }
+        self.events.publish(ClientEvent::Stderr(line));
+    }
+
+    /// The last `limit` lines, oldest first, or all of them.
+    pub fn tail(&self, limit: Option<usize>) -> Vec<StderrLine> {
+        let lines = self.lines.lock().unwrap();
+        let skip = limit.map_or(0, |limit| lines.len().saturating_sub(limit));
+        lines.iter().skip(skip).cloned().collect()
+    }
+}
@@ -4,6 +4,7 @@ mod application;
 
 use application::commands::*;
 use application::state::AppState;
+use tauri::Manager;
 use tracing_subscriber::EnvFilter;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -32,13 +33,41 @@ pub fn run() {
     let result = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
-        .manage(AppState::new())
+        .setup(|app| {
+            let config_dir = app.path().app_config_dir()?;
+            app.manage(AppState::new(&config_dir));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             start_mcp_server,
+            list_servers,
             discover_tools,
+            list_tools_page,
             get_connection_status,
+            get_server_stderr,
+            set_server_log_level,
+            get_server_logs,
+            list_server_loggers,
             call_tool,
-            disconnect_server
+            cancel_tool_call,
+            validate_tool_arguments,
+            disconnect_server,
+            list_resources,
+            list_resources_page,
+            list_resource_templates,
+            list_resource_templates_page,
+            read_resource,
+            read_resource_template,
+            subscribe_resource,
+            unsubscribe_resource,
+            list_prompts,
+            list_prompts_page,
+            get_prompt,
+            respond_to_sampling,
+            respond_to_elicitation,
+            list_roots,
+            add_root,
+            remove_root
         ])
         .run(tauri::generate_context!());
 
@@ -1,16 +1,39 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import "./App.css";
 import { ConnectionStatus } from "./components/ConnectionStatus";
 import { ChatInterface } from "./components/ChatInterface";
 import { ToolsList } from "./components/ToolsList";
-import { Tool, ConnectionStatus as ConnectionStatusType, Message, CallToolRequest, CallToolResponse } from "./types/mcp";
+import { Tool, ConnectionStatus as ConnectionStatusType, Message, CallToolRequest, CallToolResponse, ServerEvent, ToolListDiff, SamplingRequest } from "./types/mcp";
 import { LLMService, ToolChainPlan } from "./services/llm";
 import { SettingsService } from "./services/settings";
 import { UpdateSetDialog } from "./components/UpdateSetDialog";
 import { ApplicationScopeDialog } from "./components/ApplicationScopeDialog";
 import { SettingsPage } from "./components/SettingsPage";
 
+const SERVICENOW_SERVER_ID = 'servicenow';
+
+// A tool call that was cancelled rather than failed, so it isn't shown as an error
+class ToolCallCancelledError extends Error {}
+
+// Calls a tool, throwing when the call was cancelled or the tool reported an error
+const callTool = async (request: CallToolRequest): Promise<CallToolResponse> => {
+  const response = await invoke<CallToolResponse>('call_tool', { request });
+  if (response.cancelled != null) {
+    throw new ToolCallCancelledError(response.cancelled);
+  }
+  if (response.is_error) {
+    throw new Error(`${request.tool_name} reported an error: ${response.content[0]?.text || 'no details given'}`);
+  }
+  return response;
+};
+
+const describeFailure = (title: string, error: unknown) =>
+  error instanceof ToolCallCancelledError
+    ? `🚫 **Tool call cancelled**\n\n${error.message}`
+    : `❌ **${title}**\n\n${error instanceof Error ? error.message : 'Unknown error occurred'}`;
+
 function App() {
   const [connectionStatus, setConnectionStatus] = useState<ConnectionStatusType>({
     status: 'disconnected'
@@ -32,21 +55,45 @@ function App() {
   const [availableScopes, setAvailableScopes] = useState<Array<{id: string, name: string, scope: string}>>([]);
 
   useEffect(() => {
-    let interval: number;
-    
     if (connectionStatus.status === 'connected') {
       checkConnectionStatus();
-      interval = window.setInterval(checkConnectionStatus, 5000);
     }
+  }, [connectionStatus.status]);
+
+  useEffect(() => {
+    // The backend pushes connection state changes, so no polling is needed
+    const unlisten = listen<ServerEvent<ConnectionStatusType>>('mcp://connection-state', (event) => {
+      if (event.payload.server_id === SERVICENOW_SERVER_ID) {
+        setConnectionStatus(event.payload.payload);
+      }
+    });
+
+    const unlistenTools = listen<ServerEvent<ToolListDiff>>('mcp://tools-changed', (event) => {
+      if (event.payload.server_id !== SERVICENOW_SERVER_ID) return;
+      const { added, removed, changed } = event.payload.payload;
+      const replaced = new Set([...removed, ...changed].map(t => t.name));
+      setTools(prev => [...prev.filter(t => !replaced.has(t.name)), ...changed, ...added]);
+    });
+
+    // Servers started with a sampling provider ask before anything is generated
+    const unlistenSampling = listen<ServerEvent<SamplingRequest>>('mcp://sampling-request', (event) => {
+      const { server_id, payload } = event.payload;
+      const prompt = payload.request.messages.map(m => m.content.text).filter(Boolean).join('\n');
+      const approved = window.confirm(`Server '${server_id}' wants to generate a message for:\n\n${prompt}`);
+      invoke('respond_to_sampling', { serverId: server_id, requestId: payload.request_id, approved })
+        .catch(error => console.error('Failed to answer sampling request:', error));
+    });
 
     return () => {
-      if (interval) window.clearInterval(interval);
+      unlisten.then(fn => fn());
+      unlistenTools.then(fn => fn());
+      unlistenSampling.then(fn => fn());
     };
-  }, [connectionStatus.status]);
+  }, []);
 
   const checkConnectionStatus = async () => {
     try {
-      const status = await invoke<ConnectionStatusType>('get_connection_status');
+      const status = await invoke<ConnectionStatusType>('get_connection_status', { serverId: SERVICENOW_SERVER_ID });
       setConnectionStatus(status);
     } catch (error) {
       console.error('Failed to check connection status:', error);
@@ -75,6 +122,7 @@ function App() {
         // Start ServiceNow MCP server that the desktop app can control
         await invoke('start_mcp_server', {
           request: {
+            server_id: SERVICENOW_SERVER_ID,
             command: 'node',
             args: ['dist/simple-index.js'],
             cwd: '/Users/adampflantzer/desktop/my_apps/servicenow-mcp-consultancy',
@@ -87,7 +135,7 @@ function App() {
         
         // Try to discover tools with shorter timeout to prevent hanging
         const tools = await Promise.race([
-          invoke<Tool[]>('discover_tools'),
+          invoke<Tool[]>('discover_tools', { serverId: SERVICENOW_SERVER_ID }),
           new Promise<never>((_, reject) => 
             setTimeout(() => reject(new Error('Tool discovery timeout after 5 seconds')), 5000)
           )
@@ -146,7 +194,7 @@ function App() {
 
   const disconnectFromServer = async () => {
     try {
-      await invoke('disconnect_server');
+      await invoke('disconnect_server', { serverId: SERVICENOW_SERVER_ID });
       setConnectionStatus({ status: 'disconnected' });
       setTools([]);
       setMessages([]);
@@ -169,12 +217,13 @@ function App() {
       }
       
       const toolRequest: CallToolRequest = {
+        server_id: SERVICENOW_SERVER_ID,
         tool_name: step.toolName,
         arguments: resolvedArguments
       };
 
       try {
-        const toolResponse = await invoke<CallToolResponse>('call_tool', { request: toolRequest });
+        const toolResponse = await callTool(toolRequest);
         results.push({
           step: i + 1,
           toolName: step.toolName,
@@ -193,6 +242,9 @@ function App() {
         
       } catch (error) {
         console.error(`Tool chain step ${i + 1} failed:`, error);
+        if (error instanceof ToolCallCancelledError) {
+          throw error;
+        }
         throw new Error(`Step ${i + 1} (${step.toolName}) failed: ${error instanceof Error ? error.message : 'Unknown error'}`);
       }
     }
@@ -226,9 +278,20 @@ function App() {
     return obj;
   };
 
+  const showFailure = (title: string, error: unknown) => {
+    const failureMessage: Message = {
+      id: Date.now().toString(),
+      role: 'assistant',
+      content: describeFailure(title, error),
+      timestamp: new Date()
+    };
+    setMessages(prev => [...prev, failureMessage]);
+  };
+
   const fetchAvailableUpdateSets = async () => {
     try {
       const toolRequest: CallToolRequest = {
+        server_id: SERVICENOW_SERVER_ID,
         tool_name: 'query-records',
         arguments: {
           table: 'sys_update_set',
@@ -237,12 +300,13 @@ function App() {
           limit: 20
         }
       };
-      const response = await invoke<CallToolResponse>('call_tool', { request: toolRequest });
+      const response = await callTool(toolRequest);
       const responseText = response.content[0]?.text || '[]';
       const updateSets = JSON.parse(responseText.match(/\[[\s\S]*\]/)?.[0] || '[]');
       return updateSets.map((us: any) => ({ id: us.sys_id, name: us.name }));
     } catch (error) {
       console.error('Failed to fetch update sets:', error);
+      showFailure('Failed to fetch update sets', error);
       return [];
     }
   };
@@ -250,6 +314,7 @@ function App() {
   const fetchAvailableScopes = async () => {
     try {
       const toolRequest: CallToolRequest = {
+        server_id: SERVICENOW_SERVER_ID,
         tool_name: 'query-records',
         arguments: {
           table: 'sys_scope',
@@ -258,7 +323,7 @@ function App() {
           limit: 1000  // Increased to handle large instances with ~800 scopes
         }
       };
-      const response = await invoke<CallToolResponse>('call_tool', { request: toolRequest });
+      const response = await callTool(toolRequest);
       const responseText = response.content[0]?.text || '[]';
       
       // Debug: Log the raw response to see what we're getting
@@ -296,6 +361,7 @@ function App() {
       return mappedScopes;
     } catch (error) {
       console.error('Failed to fetch scopes:', error);
+      showFailure('Failed to fetch application scopes', error);
       return [{ id: 'global', name: 'Global', scope: 'global', active: true }];
     }
   };
@@ -310,13 +376,14 @@ function App() {
 
     const name = settingsService.generateUpdateSetName(description);
     const toolRequest: CallToolRequest = {
+      server_id: SERVICENOW_SERVER_ID,
       tool_name: 'create-update-set',
       arguments: {
         name: name,
         description: `Created by MCP Desktop: ${description}`
       }
     };
-    const response = await invoke<CallToolResponse>('call_tool', { request: toolRequest });
+    const response = await callTool(toolRequest);
     const responseText = response.content[0]?.text || '';
     const sysIdMatch = responseText.match(/Sys ID: ([a-f0-9]{32})/i);
     return {
@@ -335,24 +402,26 @@ function App() {
 
     console.log(`📝 Setting current update set to: ${updateSetId}`);
     const toolRequest: CallToolRequest = {
+      server_id: SERVICENOW_SERVER_ID,
       tool_name: 'set-current-update-set',
       arguments: {
         update_set_id: updateSetId
       }
     };
-    const response = await invoke<CallToolResponse>('call_tool', { request: toolRequest });
+    const response = await callTool(toolRequest);
     console.log(`✅ Update set response:`, response.content[0]?.text || 'No response');
   };
 
   const setApplicationScope = async (scopeId: string) => {
     console.log(`🎯 Setting application scope to: ${scopeId}`);
     const toolRequest: CallToolRequest = {
+      server_id: SERVICENOW_SERVER_ID,
       tool_name: 'set-application-scope',
       arguments: {
         scope: scopeId
       }
     };
-    const response = await invoke<CallToolResponse>('call_tool', { request: toolRequest });
+    const response = await callTool(toolRequest);
     console.log(`✅ Application scope set response:`, response.content[0]?.text || 'No response');
   };
 
@@ -363,6 +432,7 @@ function App() {
       
       // Test 1: Check current update set
       const currentUpdateSetRequest: CallToolRequest = {
+        server_id: SERVICENOW_SERVER_ID,
         tool_name: 'query-records',
         arguments: {
           table: 'sys_update_set',
@@ -371,13 +441,14 @@ function App() {
           limit: 5
         }
       };
-      const updateSetResponse = await invoke<CallToolResponse>('call_tool', { request: currentUpdateSetRequest });
+      const updateSetResponse = await callTool(currentUpdateSetRequest);
       console.log('📝 Current update sets:', updateSetResponse.content[0]?.text);
 
       // Test 2: Check for records in update set (customer updates)
       const settings = settingsService.getSettings();
       if (settings.updateSet.currentUpdateSet) {
         const customerUpdatesRequest: CallToolRequest = {
+          server_id: SERVICENOW_SERVER_ID,
           tool_name: 'query-records',
           arguments: {
             table: 'sys_update_xml',
@@ -386,7 +457,7 @@ function App() {
             limit: 10
           }
         };
-        const customerUpdatesResponse = await invoke<CallToolResponse>('call_tool', { request: customerUpdatesRequest });
+        const customerUpdatesResponse = await callTool(customerUpdatesRequest);
         console.log('📋 Customer updates in current update set:', customerUpdatesResponse.content[0]?.text);
       }
 
@@ -454,11 +525,12 @@ function App() {
         // Single tool execution
         const step = toolChainPlan.steps[0];
         const toolRequest: CallToolRequest = {
+          server_id: SERVICENOW_SERVER_ID,
           tool_name: step.toolName,
           arguments: step.arguments
         };
 
-        const toolResponse = await invoke<CallToolResponse>('call_tool', { request: toolRequest });
+        const toolResponse = await callTool(toolRequest);
         const naturalResponse = await llmService.processToolResponse(
           userContent,
           step.toolName,
@@ -478,7 +550,7 @@ function App() {
       const errorMessage: Message = {
         id: (Date.now() + 4).toString(),
         role: 'assistant',
-        content: `❌ **Error executing tool**\n\n${error instanceof Error ? error.message : 'Unknown error occurred'}`,
+        content: describeFailure('Error executing tool', error),
         timestamp: new Date()
       };
       setMessages(prev => [...prev, errorMessage]);
@@ -583,11 +655,12 @@ function App() {
         setMessages(prev => prev.slice(0, -1).concat([toolSelectionMessage]));
 
         const toolRequest: CallToolRequest = {
+          server_id: SERVICENOW_SERVER_ID,
           tool_name: step.toolName,
           arguments: step.arguments
         };
 
-        const toolResponse = await invoke<CallToolResponse>('call_tool', { request: toolRequest });
+        const toolResponse = await callTool(toolRequest);
 
         const naturalResponse = await llmService.processToolResponse(
           content,
@@ -609,7 +682,9 @@ function App() {
       const errorMessage: Message = {
         id: (Date.now() + 4).toString(),
         role: 'assistant',
-        content: `❌ **Error processing your request**\n\n${error instanceof Error ? error.message : 'Unknown error occurred'}\n\nI can help you with:\n${tools.map(t => `• ${t.name}: ${t.description || 'No description'}`).join('\n')}`,
+        content: error instanceof ToolCallCancelledError
+          ? `🚫 **Tool call cancelled**\n\n${error.message}`
+          : `❌ **Error processing your request**\n\n${error instanceof Error ? error.message : 'Unknown error occurred'}\n\nI can help you with:\n${tools.map(t => `• ${t.name}: ${t.description || 'No description'}`).join('\n')}`,
         timestamp: new Date()
       };
       
@@ -801,9 +876,17 @@ function App() {
                 }
               } catch (error) {
                 console.error('Failed to set update set:', error);
+                showFailure('Failed to set update set', error);
+              }
+            }}
+            onCreate={async (prefix, description) => {
+              try {
+                return await createUpdateSet(prefix, description);
+              } catch (error) {
+                showFailure('Failed to create update set', error);
+                throw error;
               }
             }}
-            onCreate={createUpdateSet}
             onCancel={() => {
               setShowUpdateSetDialog(false);
               setPendingToolExecution(null);
@@ -836,6 +919,7 @@ function App() {
                 }
               } catch (error) {
                 console.error('Failed to set application scope:', error);
+                showFailure('Failed to set application scope', error);
               }
             }}
             onCancel={() => {
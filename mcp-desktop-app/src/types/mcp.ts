@@ -8,9 +8,23 @@ export interface Tool {
   };
 }
 
+export interface ToolListDiff {
+  added: Tool[];
+  removed: Tool[];
+  changed: Tool[];
+}
+
 export interface ConnectionStatus {
   status: 'disconnected' | 'connecting' | 'connected' | 'error';
   message?: string;
+  // Negotiated during initialize, e.g. "2025-06-18"
+  protocol_version?: string;
+}
+
+export interface ServerEvent<T = unknown> {
+  server_id: string;
+  kind: string;
+  payload: T;
 }
 
 export interface Message {
@@ -20,24 +34,214 @@ export interface Message {
   timestamp: Date;
 }
 
+export type TransportKind =
+  | { type: 'stdio' }
+  | { type: 'streamable_http'; url: string; headers?: Record<string, string> }
+  | { type: 'sse'; url: string; headers?: Record<string, string> };
+
 export interface StartServerRequest {
+  server_id: string;
   command: string;
   args: string[];
   cwd?: string;
   env?: Record<string, string> | null;
+  transport?: TransportKind;
+  timeouts?: TimeoutConfig;
+  restart?: RestartPolicy;
+  shutdown?: ShutdownConfig;
+  // Opts the server into sampling; without a provider it isn't offered
+  sampling_provider?: SamplingProviderKind | null;
+}
+
+export type SamplingProviderKind = 'mock';
+
+// Milliseconds; a per-tool value beats a per-method value beats the default
+export interface TimeoutConfig {
+  default_ms?: number;
+  methods?: Record<string, number>;
+  tools?: Record<string, number>;
+  max_total_ms?: number;
+}
+
+// Restarts a stdio server whose process exits, with exponential backoff
+export interface RestartPolicy {
+  enabled?: boolean;
+  max_attempts?: number;
+  initial_backoff_ms?: number;
+  max_backoff_ms?: number;
+}
+
+// How long a stdio server gets to exit after its stdin is closed, then after SIGTERM
+export interface ShutdownConfig {
+  stdin_grace_ms?: number;
+  term_grace_ms?: number;
 }
 
 export interface CallToolRequest {
+  server_id: string;
   tool_name: string;
   arguments?: any;
+  // Pass the same id to `cancel_tool_call` to abort the call; progress events carry it too
+  call_id?: string;
+  reset_timeout_on_progress?: boolean;
+}
+
+export interface ToolProgress {
+  progress_token: string;
+  tool: string;
+  progress: number;
+  total?: number;
+  message?: string;
+}
+
+// Payload of `mcp://server-stderr` events and entries of `get_server_stderr`
+export interface StderrLine {
+  timestamp: string;
+  line: string;
+}
+
+export interface Root {
+  uri: string;
+  name?: string;
 }
 
 export interface CallToolResponse {
   content: ToolContent[];
-  is_error?: boolean;
+  is_error: boolean;
+  structured_content?: unknown;
+  // Why the call was cancelled; `content` is then empty
+  cancelled?: string | null;
+}
+
+export interface Annotations {
+  audience?: ('user' | 'assistant')[];
+  priority?: number;
+  lastModified?: string;
 }
 
+// 'text' | 'image' | 'audio' | 'resource' | 'resource_link', or a type this client doesn't know
 export interface ToolContent {
   type: string;
   text?: string;
-}
\ No newline at end of file
+  data?: string;
+  mimeType?: string;
+  resource?: ResourceContents;
+  uri?: string;
+  name?: string;
+  annotations?: Annotations;
+}
+export interface Resource {
+  uri: string;
+  name: string;
+  title?: string;
+  description?: string;
+  mimeType?: string;
+  size?: number;
+}
+
+export interface ResourceTemplate {
+  uriTemplate: string;
+  name: string;
+  title?: string;
+  description?: string;
+  mimeType?: string;
+}
+
+export type ResourceContents =
+  | { uri: string; mimeType?: string; text: string }
+  | { uri: string; mimeType?: string; blob: string };
+
+export interface ResourceUpdate {
+  uri: string;
+  contents: ResourceContents[];
+}
+
+export interface PromptArgument {
+  name: string;
+  title?: string;
+  description?: string;
+  required: boolean;
+}
+
+export interface Prompt {
+  name: string;
+  title?: string;
+  description?: string;
+  arguments?: PromptArgument[];
+}
+
+export interface PromptMessage {
+  role: 'user' | 'assistant';
+  content: ToolContent;
+}
+
+export interface GetPromptResponse {
+  description?: string;
+  messages: PromptMessage[];
+}
+
+export interface SchemaViolation {
+  // JSON pointer to the offending field, empty for the arguments object itself
+  path: string;
+  message: string;
+}
+
+export interface ModelPreferences {
+  hints?: { name?: string }[];
+  costPriority?: number;
+  speedPriority?: number;
+  intelligencePriority?: number;
+}
+
+export interface CreateMessageRequest {
+  messages: PromptMessage[];
+  modelPreferences?: ModelPreferences;
+  systemPrompt?: string;
+  includeContext?: 'none' | 'thisServer' | 'allServers';
+  temperature?: number;
+  maxTokens: number;
+  stopSequences?: string[];
+  metadata?: unknown;
+}
+
+// Payload of mcp://sampling-request; answer with respond_to_sampling
+export interface SamplingRequest {
+  request_id: string;
+  request: CreateMessageRequest;
+}
+
+export type ElicitAction = 'accept' | 'decline' | 'cancel';
+
+// Payload of mcp://elicitation-request; answer with respond_to_elicitation
+export interface ElicitationRequest {
+  request_id: string;
+  request: {
+    message: string;
+    // Object schema whose properties are all string, number, integer or boolean
+    requestedSchema: Record<string, unknown>;
+  };
+}
+
+export type LoggingLevel =
+  | 'debug'
+  | 'info'
+  | 'notice'
+  | 'warning'
+  | 'error'
+  | 'critical'
+  | 'alert'
+  | 'emergency';
+
+export interface LogEntry {
+  timestamp: string;
+  level: LoggingLevel;
+  logger?: string | null;
+  data: unknown;
+}
+
+// Unset fields don't filter; min_level keeps that level and more severe ones
+export interface LogFilter {
+  min_level?: LoggingLevel;
+  logger?: string;
+  limit?: number;
+}